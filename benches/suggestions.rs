@@ -1,4 +1,9 @@
-use std::{collections::HashMap, hint::black_box};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    collections::HashMap,
+    hint::black_box,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
 
 use ahash::RandomState;
 use criterion::{criterion_group, criterion_main, Criterion};
@@ -7,6 +12,62 @@ use regex::Regex;
 
 use upodesh::avro::Suggest;
 
+/// Counts the heap allocations made while `COUNTING` is set, so that the
+/// traversal cost can be tracked alongside the latency without slowing down
+/// the other benches.
+struct CountingAlloc;
+
+static COUNTING: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if COUNTING.load(Ordering::Relaxed) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn count_allocations<T>(f: impl FnOnce() -> T) -> usize {
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    COUNTING.store(true, Ordering::Relaxed);
+    black_box(f());
+    COUNTING.store(false, Ordering::Relaxed);
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+fn upodesh_allocations(_: &mut Criterion) {
+    let suggest = Suggest::new();
+    // The first query loads the data the next ones share
+    suggest.suggest("a");
+    upodesh::bangla::suggest("আ");
+
+    for input in ["a", "arO", "bistari", "aaaaaaaaaaaa", "bishshobiddaloy"] {
+        let count = count_allocations(|| suggest.suggest(input));
+        println!("upodesh avro {input}: {count} allocations");
+    }
+
+    for input in ["আমা", "কম্পি", "কনট্রো"] {
+        let count = count_allocations(|| upodesh::bangla::suggest(input));
+        println!("upodesh bangla {input}: {count} allocations");
+    }
+}
+
 fn upodesh_avro_benchmark(c: &mut Criterion) {
     let suggest = Suggest::new();
 
@@ -21,6 +82,23 @@ fn upodesh_avro_benchmark(c: &mut Criterion) {
     });
 }
 
+fn upodesh_avro_stress_benchmark(c: &mut Criterion) {
+    let suggest = Suggest::new();
+
+    // Long inputs where many transliterations and suffixes lead to the same
+    // FST states
+    for input in [
+        "aaaaaaaaaaaa",
+        "oooooooooooo",
+        "bishshobiddaloy",
+        "ononnoshadharon",
+    ] {
+        c.bench_function(&format!("upodesh avro stress {input}"), |b| {
+            b.iter(|| suggest.suggest(black_box(input)))
+        });
+    }
+}
+
 fn regex_avro_benchmark(c: &mut Criterion) {
     let table: [(&str, &[&str]); 26] = [
         ("a", &["a", "aa", "e", "oi", "o", "nya", "y"]),
@@ -183,7 +261,7 @@ fn regex_bangla_benchmark(c: &mut Criterion) {
         database
             .get(table)
             .unwrap()
-            .into_iter()
+            .iter()
             .filter(|i| rgx.is_match(i))
             .cloned()
             .collect()
@@ -207,10 +285,16 @@ fn regex_bangla_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches_avro, upodesh_avro_benchmark, regex_avro_benchmark);
+criterion_group!(
+    benches_avro,
+    upodesh_avro_benchmark,
+    upodesh_avro_stress_benchmark,
+    regex_avro_benchmark
+);
 criterion_group!(
     benches_bangla,
     upodesh_bangla_benchmark,
     regex_bangla_benchmark
 );
-criterion_main!(benches_avro, benches_bangla);
+criterion_group!(benches_allocations, upodesh_allocations);
+criterion_main!(benches_avro, benches_bangla, benches_allocations);
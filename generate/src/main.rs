@@ -1,7 +1,7 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     env::{args, var_os},
-    fs::{File, read, read_to_string},
+    fs::{File, read, read_to_string, write},
     io::BufWriter,
     path::PathBuf,
 };
@@ -16,6 +16,54 @@ pub struct Block {
     pub transliterate: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub entire_block_optional: Option<bool>,
+    /// The weights of the transliterations ranking the more common ones
+    /// first. The transliterations left out weigh 0.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub weights: BTreeMap<String, u64>,
+    /// The blocks used instead of this one when their condition holds. Rules
+    /// have no rules.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rule {
+    pub when: Condition,
+    #[serde(flatten)]
+    pub block: Block,
+}
+
+/// The condition of a rule, every part of which must hold.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Condition {
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub word_start: bool,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub word_end: bool,
+    /// `consonant` or `vowel`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub before: String,
+}
+
+impl Condition {
+    /// The rule flags read by `upodesh::avro::rule`.
+    fn flags(&self) -> u32 {
+        let after = match self.after.as_deref() {
+            None => 0,
+            Some("consonant") => 1 << 3,
+            Some("vowel") => 1 << 4,
+            Some(after) => panic!("Unknown rule condition `after: {after}`"),
+        };
+
+        u32::from(self.word_start) << 1 | u32::from(self.word_end) << 2 | after
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
 
 #[derive(Deserialize)]
@@ -23,6 +71,17 @@ pub struct Block {
 pub struct RegexBlock {
     pub transliterate: String,
     pub entire_block_optional: Option<bool>,
+    #[serde(default)]
+    pub weights: BTreeMap<String, u64>,
+    #[serde(default)]
+    pub rules: Vec<RegexRule>,
+}
+
+#[derive(Deserialize)]
+pub struct RegexRule {
+    pub when: Condition,
+    #[serde(flatten)]
+    pub block: RegexBlock,
 }
 
 fn generate_words_fst() {
@@ -37,12 +96,14 @@ fn generate_words_fst() {
     let words = read_to_string(parent.join("data/source-words.txt"))
         .expect("Failed to read source words file");
 
-    let mut words = words.lines().map(str::trim).collect::<Vec<_>>();
+    // Sort the encoded words, as ZWNJ and ZWJ are ordered differently once
+    // encoded into a single byte
+    let words = words
+        .lines()
+        .map(|word| word.trim().chars().map(|c| c as u8).collect::<Vec<u8>>())
+        .collect::<BTreeSet<_>>();
 
-    words.sort();
-
-    for word in words {
-        let numbered_word = word.chars().map(|c| c as u8).collect::<Vec<u8>>();
+    for numbered_word in words {
         fst.add(&numbered_word).expect("Failed to add word to FST");
     }
 
@@ -52,30 +113,177 @@ fn generate_words_fst() {
 fn generate_patterns_fst() {
     let root = PathBuf::from(var_os("CARGO_MANIFEST_DIR").unwrap());
     let parent = root.parent().unwrap();
-    let dest = parent.join("src").join("avro").join("patterns.fst");
+    let dest = parent.join("src").join("avro");
 
-    let file = File::create(dest).expect("Failed to create patterns.fst");
+    let file = File::create(dest.join("patterns.fst")).expect("Failed to create patterns.fst");
     let writer = BufWriter::new(file);
 
     let mut fst = Builder::new(writer).unwrap();
-    let patterns: HashMap<String, Block> = serde_json::from_slice(
+    let patterns: BTreeMap<String, Block> = serde_json::from_slice(
         &read(parent.join("data/preprocessed-patterns.json"))
             .expect("Failed to read source patterns file"),
     )
     .unwrap();
 
-    let mut patterns = patterns.keys().map(|s| s.as_str()).collect::<Vec<_>>();
-
-    patterns.sort();
-
-    for pattern in patterns {
+    // The value of each pattern is the index of its block in the block table
+    for (index, pattern) in patterns.keys().enumerate() {
         let numbered_word = pattern.chars().map(|c| c as u8).collect::<Vec<u8>>();
-        fst.add(&numbered_word)
+        fst.insert(&numbered_word, index as u64)
             .expect("Failed to add pattern to FST");
     }
 
     fst.finish()
         .expect("Failed to finish patterns FST generation");
+
+    // The rules of the patterns are stored after the blocks of the patterns
+    let always = Condition::default();
+    let rules = patterns
+        .values()
+        .flat_map(|block| block.rules.iter().map(|rule| (&rule.block, &rule.when)));
+    let blocks = patterns
+        .values()
+        .map(|block| (block, &always))
+        .chain(rules)
+        .collect::<Vec<_>>();
+
+    write(
+        dest.join("blocks.bin"),
+        block_table(patterns.len(), &blocks),
+    )
+    .expect("Failed to write blocks.bin");
+
+    let file = File::create(dest.join("blocks.fst")).expect("Failed to create blocks.fst");
+    let mut fst = Builder::new(BufWriter::new(file)).unwrap();
+
+    // Each block's transliterations are stored after its index, so that a
+    // block is the sub-automaton reached by walking the two index bytes. The
+    // value of a transliteration is its weight.
+    let mut keys = BTreeMap::new();
+    for (index, (block, _)) in blocks.iter().enumerate() {
+        for transliteration in &block.transliterate {
+            let mut key = (index as u16).to_be_bytes().to_vec();
+            key.extend(transliteration.chars().map(|c| c as u8));
+            let weight = block.weights.get(transliteration).copied().unwrap_or(0);
+            keys.insert(key, weight);
+        }
+    }
+
+    for (key, weight) in keys {
+        fst.insert(&key, weight)
+            .expect("Failed to add transliteration to FST");
+    }
+
+    fst.finish().expect("Failed to finish blocks FST generation");
+}
+
+/// Packs the records of the blocks, the first `patterns` of which are the
+/// blocks of the patterns and the others their rules, into the table format
+/// read by `upodesh::avro::table`.
+fn block_table(patterns: usize, blocks: &[(&Block, &Condition)]) -> Vec<u8> {
+    let mut table = b"UPBT".to_vec();
+    table.extend((blocks.len() as u32).to_le_bytes());
+
+    let mut keys = String::new();
+    let mut first_rule = patterns;
+
+    for (block, condition) in blocks {
+        let flags = u32::from(block.entire_block_optional.unwrap_or(false)) | condition.flags();
+        let rule_count = block.rules.len();
+        let record = [
+            flags,
+            if rule_count > 0 { first_rule as u32 } else { 0 },
+            rule_count as u32,
+            keys.len() as u32,
+            condition.before.len() as u32,
+        ];
+
+        for value in record {
+            table.extend(value.to_le_bytes());
+        }
+
+        first_rule += rule_count;
+        keys.push_str(&condition.before);
+    }
+
+    table.extend(keys.as_bytes());
+
+    table
+}
+
+fn generate_loanwords_fst() {
+    let root = PathBuf::from(var_os("CARGO_MANIFEST_DIR").unwrap());
+    let parent = root.parent().unwrap();
+    let dest = parent.join("src").join("avro");
+
+    let source = read_to_string(parent.join("data/source-loanwords.txt"))
+        .expect("Failed to read source loanwords file");
+
+    // An English word may have several accepted Bangla spellings
+    let mut loanwords: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for line in source.lines().filter(|line| !line.trim().is_empty()) {
+        let (english, bangla) = line
+            .split_once('\t')
+            .expect("Loanwords must be separated by a tab");
+        loanwords
+            .entry(english.trim().to_ascii_lowercase())
+            .or_default()
+            .push(bangla.trim());
+    }
+
+    let file = File::create(dest.join("loanwords.fst")).expect("Failed to create loanwords.fst");
+    let mut fst = Builder::new(BufWriter::new(file)).unwrap();
+
+    // The value of each English word is the offset and the length of its
+    // spellings, separated by newlines, in the spelling table
+    let mut spellings = b"UPLW".to_vec();
+    for (english, bangla) in loanwords {
+        let span = bangla.join("\n");
+        let value = ((spellings.len() as u64) << 32) | span.len() as u64;
+        spellings.extend(span.as_bytes());
+
+        fst.insert(english.as_bytes(), value)
+            .expect("Failed to add loanword to FST");
+    }
+
+    fst.finish()
+        .expect("Failed to finish loanwords FST generation");
+
+    write(dest.join("loanwords.bin"), spellings).expect("Failed to write loanwords.bin");
+}
+
+fn generate_english_fst() {
+    let root = PathBuf::from(var_os("CARGO_MANIFEST_DIR").unwrap());
+    let parent = root.parent().unwrap();
+    let dest = parent.join("src").join("avro").join("english.fst");
+
+    let source = read_to_string(parent.join("data/source-english-words.txt"))
+        .expect("Failed to read source English words file");
+
+    // The words are looked up in lowercase, with the casing of their source
+    // spelling as the value: 0 lowercase, 1 capitalized, 2 uppercase
+    let mut words = BTreeMap::new();
+    for word in source.lines().map(str::trim).filter(|word| !word.is_empty()) {
+        let casing = if word.len() > 1 && word.bytes().all(|b| b.is_ascii_uppercase()) {
+            2
+        } else if word.starts_with(|c: char| c.is_ascii_uppercase()) {
+            1
+        } else {
+            0
+        };
+
+        words.insert(word.to_ascii_lowercase(), casing);
+    }
+
+    let file = File::create(dest).expect("Failed to create english.fst");
+    let mut fst = Builder::new(BufWriter::new(file)).unwrap();
+
+    for (word, casing) in words {
+        fst.insert(word.as_bytes(), casing)
+            .expect("Failed to add English word to FST");
+    }
+
+    fst.finish()
+        .expect("Failed to finish English words FST generation");
 }
 
 fn generate_regex_exploded_patterns(source: &str, dest: &str) {
@@ -88,31 +296,55 @@ fn generate_regex_exploded_patterns(source: &str, dest: &str) {
     let mut patterns: BTreeMap<String, Block> = BTreeMap::new();
 
     for (pattern, block) in regex_patterns {
-        // Unique and non-empty patterns only
-        let mut exploded: Vec<String> = explode(&block.transliterate)
-            .unwrap()
-            .into_iter()
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .filter(|s| !s.is_empty())
-            .collect();
-
-        // sort the patterns for consistency
-        exploded.sort();
-
-        patterns.insert(
-            pattern,
-            Block {
-                transliterate: exploded,
-                entire_block_optional: block.entire_block_optional,
-            },
-        );
+        patterns.insert(pattern.clone(), explode_block(&pattern, block));
     }
 
     serde_json::to_writer_pretty(file, &patterns)
         .expect("Failed to write exploded patterns to file");
 }
 
+/// Explodes the transliterations of the `block` of `pattern` and of its rules.
+fn explode_block(pattern: &str, block: RegexBlock) -> Block {
+    // Unique and non-empty patterns only
+    let mut exploded: Vec<String> = explode(&block.transliterate)
+        .unwrap()
+        .into_iter()
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    // sort the patterns for consistency
+    exploded.sort();
+
+    for alternative in block.weights.keys() {
+        assert!(
+            exploded.contains(alternative),
+            "Weighted `{alternative}` is not a transliteration of `{pattern}`"
+        );
+    }
+
+    let rules = block
+        .rules
+        .into_iter()
+        .map(|rule| {
+            assert!(rule.block.rules.is_empty(), "Rules of `{pattern}` have rules");
+
+            Rule {
+                when: rule.when,
+                block: explode_block(pattern, rule.block),
+            }
+        })
+        .collect();
+
+    Block {
+        transliterate: exploded,
+        entire_block_optional: block.entire_block_optional,
+        weights: block.weights,
+        rules,
+    }
+}
+
 fn main() {
     let args = args().collect::<Vec<String>>();
 
@@ -130,5 +362,7 @@ fn main() {
     } else {
         generate_words_fst();
         generate_patterns_fst();
+        generate_loanwords_fst();
+        generate_english_fst();
     }
 }
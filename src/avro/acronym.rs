@@ -0,0 +1,74 @@
+/// The Bangla names of the Latin letters, from `A` to `Z`.
+const LETTER_NAMES: [&str; 26] = [
+    "এ",
+    "বি",
+    "সি",
+    "ডি",
+    "ই",
+    "এফ",
+    "জি",
+    "এইচ",
+    "আই",
+    "জে",
+    "কে",
+    "এল",
+    "এম",
+    "এন",
+    "ও",
+    "পি",
+    "কিউ",
+    "আর",
+    "এস",
+    "টি",
+    "ইউ",
+    "ভি",
+    "ডব্লিউ",
+    "এক্স",
+    "ওয়াই",
+    "জেড",
+];
+
+/// Returns whether `input` looks like an acronym, that is at least two
+/// letters, all of them uppercase.
+///
+/// A single uppercase letter is left alone, as Avro uses them for other
+/// letters (`T` is ট while `t` is ত).
+pub fn is_acronym(input: &str) -> bool {
+    input.len() >= 2 && input.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+/// Spells an acronym with the Bangla names of its letters, e.g. `BBC` is
+/// বিবিসি.
+///
+/// Returns `None` if `input` is not an acronym.
+pub fn spell_acronym(input: &str) -> Option<String> {
+    let input = input.trim();
+
+    if !is_acronym(input) {
+        return None;
+    }
+
+    Some(
+        input
+            .bytes()
+            .map(|b| LETTER_NAMES[usize::from(b - b'A')])
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spell_acronym() {
+        assert_eq!(spell_acronym("BBC").unwrap(), "বিবিসি");
+        assert_eq!(spell_acronym("USA").unwrap(), "ইউএসএ");
+        assert_eq!(spell_acronym(" WHO ").unwrap(), "ডব্লিউএইচও");
+        assert_eq!(spell_acronym("XYZ").unwrap(), "এক্সও\u{09DF}াইজেড");
+        assert_eq!(spell_acronym("B"), None);
+        assert_eq!(spell_acronym("Bbc"), None);
+        assert_eq!(spell_acronym("BB1"), None);
+        assert_eq!(spell_acronym(""), None);
+    }
+}
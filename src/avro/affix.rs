@@ -0,0 +1,150 @@
+/// Avro style replacements for punctuation typed around a word. The full stop
+/// is only replaced when it ends the word, see [`push_punctuation`].
+const PUNCTUATION: [(char, char); 2] = [('.', '।'), ('$', '৳')];
+
+/// A Roman input split into the word to transliterate and the
+/// punctuation typed before and after it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Affixes<'a> {
+    pub prefix: &'a str,
+    pub core: &'a str,
+    pub suffix: &'a str,
+}
+
+impl<'a> Affixes<'a> {
+    /// Peels the leading and trailing punctuation off `input`.
+    ///
+    /// Everything other than ASCII alphanumerics and the Avro separator
+    /// `` ` `` is considered punctuation.
+    ///
+    /// The punctuation inside the word is left in the core, like the comma of
+    /// `ami,tumi.`, and is then dropped when the core is transliterated as a
+    /// single word.
+    pub fn split(input: &'a str) -> Self {
+        let input = input.trim();
+        let start = input.find(is_word_char).unwrap_or(input.len());
+        let end = input
+            .rfind(is_word_char)
+            .map_or(start, |i| i + 1)
+            .max(start);
+
+        Affixes {
+            prefix: &input[..start],
+            core: &input[start..end],
+            suffix: &input[end..],
+        }
+    }
+
+    /// Puts the peeled punctuation back around `word`, optionally converting
+    /// it the way Avro Phonetic does (`$` to `৳`, and a single `.` after the
+    /// word to `।`).
+    pub fn wrap(&self, word: &str, convert: bool) -> String {
+        let mut result = String::with_capacity(self.prefix.len() + word.len() + self.suffix.len());
+
+        // The punctuation typed with no word ends it, so `.` alone is a full
+        // stop
+        push_punctuation(&mut result, self.prefix, convert, word.is_empty());
+        result.push_str(word);
+        push_punctuation(&mut result, self.suffix, convert, true);
+
+        result
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '`'
+}
+
+/// Pushes the `punctuation` typed before or `after_word`, converted if asked.
+///
+/// A full stop is only converted after the word, and not in a run of dots
+/// like an ellipsis.
+fn push_punctuation(result: &mut String, punctuation: &str, convert: bool, after_word: bool) {
+    if !convert {
+        result.push_str(punctuation);
+        return;
+    }
+
+    let chars: Vec<char> = punctuation.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let in_run = (i > 0 && chars[i - 1] == '.') || chars.get(i + 1) == Some(&'.');
+        if c == '.' && (!after_word || in_run) {
+            result.push(c);
+            continue;
+        }
+
+        let replacement = PUNCTUATION
+            .iter()
+            .find(|(from, _)| *from == c)
+            .map_or(c, |&(_, to)| to);
+        result.push(replacement);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            Affixes::split("(ami),"),
+            Affixes {
+                prefix: "(",
+                core: "ami",
+                suffix: "),"
+            }
+        );
+        assert_eq!(
+            Affixes::split(" \"ke?\" "),
+            Affixes {
+                prefix: "\"",
+                core: "ke",
+                suffix: "?\""
+            }
+        );
+        assert_eq!(
+            Affixes::split("ami,tumi."),
+            Affixes {
+                prefix: "",
+                core: "ami,tumi",
+                suffix: "."
+            }
+        );
+        assert_eq!(
+            Affixes::split("6t``"),
+            Affixes {
+                prefix: "",
+                core: "6t``",
+                suffix: ""
+            }
+        );
+        assert_eq!(
+            Affixes::split("..."),
+            Affixes {
+                prefix: "...",
+                core: "",
+                suffix: ""
+            }
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        let affixes = Affixes::split("$taka.");
+
+        assert_eq!(affixes.wrap("টাকা", false), "$টাকা.");
+        assert_eq!(affixes.wrap("টাকা", true), "৳টাকা।");
+        assert_eq!(Affixes::split("(ami),").wrap("আমি", true), "(আমি),");
+        assert_eq!(Affixes::split("(ami.)").wrap("আমি", true), "(আমি।)");
+
+        // Only a single full stop after the word is converted
+        assert_eq!(Affixes::split("...ami!!").wrap("আমি", true), "...আমি!!");
+        assert_eq!(Affixes::split(".ami").wrap("আমি", true), ".আমি");
+        assert_eq!(Affixes::split("ami..").wrap("আমি", true), "আমি..");
+        assert_eq!(Affixes::split("ami...").wrap("আমি", true), "আমি...");
+        assert_eq!(Affixes::split("ami?...").wrap("আমি", true), "আমি?...");
+        assert_eq!(Affixes::split(".").wrap("", true), "।");
+        assert_eq!(Affixes::split("...").wrap("", true), "...");
+    }
+}
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+/// A table of corrections for exact Roman inputs, like the autocorrect
+/// dictionary of Avro Phonetic or personal shortcuts (`bd` to বাংলাদেশ).
+///
+/// A correction is either a Bangla word, which is suggested as is, or a Roman
+/// spelling, which is transliterated like any other input.
+#[derive(Clone, Debug, Default)]
+pub struct Autocorrect {
+    entries: HashMap<String, String>,
+}
+
+impl Autocorrect {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a table in the JSON format of the Avro Phonetic autocorrect
+    /// dictionary, an object mapping the inputs to their corrections:
+    ///
+    /// ```json
+    /// { "bd": "বাংলাদেশ", "aamar": "amar" }
+    /// ```
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let entries = serde_json::from_str(json)?;
+
+        Ok(Autocorrect { entries })
+    }
+
+    /// Adds or replaces the correction of `input`.
+    pub fn insert(&mut self, input: &str, correction: &str) {
+        self.entries.insert(input.to_owned(), correction.to_owned());
+    }
+
+    /// Returns the correction of the exact `input`, if any.
+    pub fn get(&self, input: &str) -> Option<Correction<'_>> {
+        let correction = self.entries.get(input.trim())?;
+
+        if correction.chars().any(is_bangla) {
+            Some(Correction::Bangla(correction))
+        } else {
+            Some(Correction::Roman(correction))
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The correction of an input in an [`Autocorrect`] table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Correction<'a> {
+    /// A Bangla word, suggested as is.
+    Bangla(&'a str),
+    /// A Roman spelling to transliterate instead of the input.
+    Roman(&'a str),
+}
+
+fn is_bangla(c: char) -> bool {
+    ('\u{0980}'..='\u{09FF}').contains(&c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autocorrect() {
+        let mut autocorrect =
+            Autocorrect::from_json(r#"{ "bd": "বাংলাদেশ", "aamar": "amar" }"#).unwrap();
+        assert_eq!(autocorrect.len(), 2);
+
+        assert_eq!(autocorrect.get("bd"), Some(Correction::Bangla("বাংলাদেশ")));
+        assert_eq!(autocorrect.get(" aamar "), Some(Correction::Roman("amar")));
+        assert_eq!(autocorrect.get("amar"), None);
+        assert_eq!(autocorrect.get("BD"), None);
+
+        autocorrect.insert("bd", "Bangladesh");
+        assert_eq!(autocorrect.get("bd"), Some(Correction::Roman("Bangladesh")));
+
+        assert!(Autocorrect::from_json("[]").is_err());
+        assert!(Autocorrect::new().is_empty());
+    }
+}
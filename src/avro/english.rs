@@ -0,0 +1,66 @@
+use crate::fst::FstTree;
+
+/// The casing value of a capitalized word, like `Google`.
+const CAPITALIZED: u64 = 1;
+
+/// The casing value of an uppercase word, like `NASA`.
+const UPPERCASE: u64 = 2;
+
+/// A list of English words, for suggesting the English word typed instead
+/// of its transliteration.
+///
+/// The words are the lowercase keys of an FST map, whose values are the
+/// casing of their usual spelling.
+pub(crate) struct EnglishWords<D: AsRef<[u8]>> {
+    words: FstTree<D>,
+}
+
+impl<D: AsRef<[u8]>> EnglishWords<D> {
+    pub fn from_fst(data: D) -> EnglishWords<D> {
+        EnglishWords {
+            words: FstTree::from_fst(data),
+        }
+    }
+
+    /// Returns `word` if it is a known English word, in its usual casing if
+    /// it was typed in lowercase, like `Google` for `google`. Otherwise it is
+    /// returned as typed.
+    pub fn get(&self, word: &str) -> Option<String> {
+        if word.is_empty() || !word.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let casing = self.words.get(&word.to_ascii_lowercase())?;
+
+        if word.bytes().any(|b| b.is_ascii_uppercase()) {
+            return Some(word.to_owned());
+        }
+
+        let word = match casing {
+            CAPITALIZED => word[..1].to_ascii_uppercase() + &word[1..],
+            UPPERCASE => word.to_ascii_uppercase(),
+            _ => word.to_owned(),
+        };
+
+        Some(word)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_english_words() {
+        let words = EnglishWords::from_fst(include_bytes!("english.fst").as_slice());
+
+        assert_eq!(words.get("hello").unwrap(), "hello");
+        assert_eq!(words.get("Hello").unwrap(), "Hello");
+        assert_eq!(words.get("google").unwrap(), "Google");
+        assert_eq!(words.get("nasa").unwrap(), "NASA");
+        assert_eq!(words.get("may").unwrap(), "may");
+        assert_eq!(words.get("ami"), None);
+        assert_eq!(words.get("hello!"), None);
+        assert_eq!(words.get(""), None);
+    }
+}
@@ -0,0 +1,359 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+    ops::Add,
+};
+
+use crate::{avro::PatternSet, segment::is_consonant};
+
+/// How natural a way of typing a word is, summed over its keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Cost {
+    /// The letters left untyped: the inherent vowels between consonants and
+    /// the common suffixes which have keys of their own.
+    skipped: usize,
+    /// The patterns typed where another pattern is weighted higher, like `s`
+    /// for শ, or where only other transliterations are weighted, like `n` for
+    /// ং.
+    secondary: usize,
+    keys: usize,
+    /// The weights of the transliterations typed, the highest first.
+    weight: u64,
+    /// The other transliterations of the patterns typed, the fewest first.
+    alternatives: usize,
+}
+
+impl Ord for Cost {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.skipped
+            .cmp(&other.skipped)
+            .then(self.secondary.cmp(&other.secondary))
+            .then(self.keys.cmp(&other.keys))
+            .then(other.weight.cmp(&self.weight))
+            .then(self.alternatives.cmp(&other.alternatives))
+    }
+}
+
+impl PartialOrd for Cost {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Cost {
+    type Output = Cost;
+
+    fn add(self, other: Cost) -> Cost {
+        Cost {
+            skipped: self.skipped + other.skipped,
+            secondary: self.secondary + other.secondary,
+            keys: self.keys + other.keys,
+            weight: self.weight + other.weight,
+            alternatives: self.alternatives + other.alternatives,
+        }
+    }
+}
+
+/// The patterns of a scheme giving every transliteration, for finding the
+/// inputs typing a word.
+pub(crate) struct KeyTable {
+    keys: HashMap<String, Vec<(String, Cost)>>,
+    /// The length in bytes of the longest transliteration.
+    longest: usize,
+    /// The pattern typing the inherent vowel between two consonants, which
+    /// transliterates to nothing, like `o` in Avro Phonetic.
+    inherent_vowel: Option<String>,
+    common_suffixes: Vec<String>,
+}
+
+impl KeyTable {
+    pub(crate) fn new(patterns: &PatternSet) -> Self {
+        let reverse = patterns.reverse();
+
+        // The highest weight of every pattern and the number of its
+        // transliterations
+        let mut pattern_weights: HashMap<&str, (u64, usize)> = HashMap::new();
+        for (pattern, weight) in reverse.values().flatten() {
+            let (highest, count) = pattern_weights.entry(pattern).or_default();
+            *highest = (*highest).max(*weight);
+            *count += 1;
+        }
+
+        let keys = reverse
+            .iter()
+            .map(|(transliteration, patterns)| {
+                let best = patterns.iter().map(|(_, weight)| *weight).max();
+                let keys = patterns
+                    .iter()
+                    .map(|(pattern, weight)| {
+                        let (highest, count) = pattern_weights[pattern.as_str()];
+                        // A pattern weighted for other transliterations only
+                        // falls back to this one, like `n` for ং
+                        let secondary = Some(*weight) < best || (*weight == 0 && highest > 0);
+                        let cost = Cost {
+                            skipped: skipped_vowels(transliteration),
+                            secondary: usize::from(secondary),
+                            keys: pattern.len(),
+                            weight: *weight,
+                            alternatives: count - 1,
+                        };
+                        (pattern.clone(), cost)
+                    })
+                    .collect();
+                (transliteration.clone(), keys)
+            })
+            .collect();
+
+        let inherent_vowel = reverse
+            .get("অ")
+            .into_iter()
+            .flatten()
+            .map(|(pattern, _)| pattern)
+            .filter(|pattern| patterns.is_optional(pattern))
+            .min_by_key(|pattern| (pattern.len(), pattern.as_str()))
+            .cloned();
+
+        KeyTable {
+            longest: reverse.keys().map(String::len).max().unwrap_or(0),
+            keys,
+            inherent_vowel,
+            common_suffixes: patterns
+                .common_suffixes
+                .iter()
+                .map(|suffix| suffix.to_string())
+                .collect(),
+        }
+    }
+
+    /// Returns the most natural inputs typing `word` which are `accepted`:
+    /// the ones typing the inherent vowels, then the patterns weighted for
+    /// their transliterations, with the fewest keys, the highest weights and the least
+    /// ambiguous patterns. Only the accepted inputs ranked the same as the
+    /// best one are returned.
+    ///
+    /// The inputs are tried from the most natural, at most `limit` of them.
+    pub(crate) fn inputs(
+        &self,
+        word: &str,
+        mut accepted: impl FnMut(&str) -> bool,
+        limit: usize,
+    ) -> Vec<String> {
+        let mut inputs = Vec::new();
+        let mut best = None;
+
+        for (input, cost) in KeyGraph::new(self, word).best_inputs().take(limit) {
+            if best.is_some_and(|best| cost > best) {
+                break;
+            }
+            if accepted(&input) {
+                best = Some(cost);
+                inputs.push(input);
+            }
+        }
+
+        inputs
+    }
+}
+
+/// The keys typing a part of a word, and where the part ends.
+struct Edge {
+    keys: String,
+    end: usize,
+    cost: Cost,
+}
+
+/// The ways of typing a word with the patterns of a scheme: the patterns
+/// whose transliterations follow one another in the word, from every
+/// position of the word.
+struct KeyGraph {
+    /// The edges from every byte position of the word. The common suffixes,
+    /// which need no key, have empty keys.
+    edges: Vec<Vec<Edge>>,
+    /// The best cost of typing the rest of the word from every position, if
+    /// it can be typed.
+    best: Vec<Option<Cost>>,
+}
+
+impl KeyGraph {
+    fn new(table: &KeyTable, word: &str) -> Self {
+        let boundaries: Vec<_> = word
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain([word.len()])
+            .collect();
+
+        let mut edges: Vec<Vec<Edge>> = (0..=word.len()).map(|_| Vec::new()).collect();
+        for (n, &start) in boundaries.iter().enumerate() {
+            for &end in boundaries[n + 1..]
+                .iter()
+                .take_while(|&&end| end - start <= table.longest)
+            {
+                let text = &word[start..end];
+
+                let mut keys: Vec<_> = table
+                    .keys
+                    .get(text)
+                    .into_iter()
+                    .flatten()
+                    .cloned()
+                    .collect();
+                // The common suffixes are added after a pattern, but typing
+                // them is more natural if they have keys, like `w` for ্ব
+                if start > 0 && table.common_suffixes.iter().any(|s| s == text) {
+                    let cost = Cost {
+                        skipped: usize::from(table.keys.contains_key(text)),
+                        ..Cost::default()
+                    };
+                    keys.push((String::new(), cost));
+                }
+
+                let followed = text.chars().last().is_some_and(is_consonant)
+                    && word[end..].chars().next().is_some_and(takes_inherent_vowel);
+
+                for (keys, cost) in keys {
+                    match &table.inherent_vowel {
+                        Some(vowel) if followed => {
+                            edges[start].push(Edge {
+                                keys: format!("{keys}{vowel}"),
+                                end,
+                                cost: cost
+                                    + Cost {
+                                        keys: vowel.len(),
+                                        ..Cost::default()
+                                    },
+                            });
+                            edges[start].push(Edge {
+                                keys,
+                                end,
+                                cost: cost
+                                    + Cost {
+                                        skipped: 1,
+                                        ..Cost::default()
+                                    },
+                            });
+                        }
+                        _ => edges[start].push(Edge { keys, end, cost }),
+                    }
+                }
+            }
+        }
+
+        let mut best = vec![None; word.len() + 1];
+        best[word.len()] = Some(Cost::default());
+        for &start in boundaries.iter().rev().skip(1) {
+            best[start] = edges[start]
+                .iter()
+                .filter_map(|edge| Some(edge.cost + best[edge.end]?))
+                .min();
+        }
+
+        KeyGraph { edges, best }
+    }
+
+    /// Returns the distinct inputs typing the whole word with their costs,
+    /// the best first.
+    ///
+    /// The partial inputs are walked from the one with the best cost once
+    /// completed, which is known from `best`, so every input is found after
+    /// the better ones.
+    fn best_inputs(&self) -> BestInputs<'_> {
+        let mut queue = BinaryHeap::new();
+        if let Some(best) = self.best[0] {
+            queue.push(Reverse((best, String::new(), 0, Cost::default())));
+        }
+
+        BestInputs {
+            graph: self,
+            queue,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+/// The iterator returned by [`KeyGraph::best_inputs`].
+struct BestInputs<'g> {
+    graph: &'g KeyGraph,
+    /// The partial inputs, by their cost once completed, with their position
+    /// in the word and their own cost.
+    queue: BinaryHeap<Reverse<(Cost, String, usize, Cost)>>,
+    seen: HashSet<(usize, String)>,
+}
+
+impl Iterator for BestInputs<'_> {
+    type Item = (String, Cost);
+
+    fn next(&mut self) -> Option<(String, Cost)> {
+        let end = self.graph.edges.len() - 1;
+
+        while let Some(Reverse((_, typed, position, cost))) = self.queue.pop() {
+            if !self.seen.insert((position, typed.clone())) {
+                continue;
+            }
+            if position == end {
+                return Some((typed, cost));
+            }
+
+            for edge in &self.graph.edges[position] {
+                let Some(rest) = self.graph.best[edge.end] else {
+                    continue;
+                };
+                let cost = cost + edge.cost;
+                let typed = format!("{typed}{}", edge.keys);
+
+                self.queue
+                    .push(Reverse((cost + rest, typed, edge.end, cost)));
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the number of inherent vowels read inside `transliteration`, which
+/// its pattern types with no key, like `shn` for সং.
+fn skipped_vowels(transliteration: &str) -> usize {
+    let chars: Vec<_> = transliteration.chars().collect();
+    chars
+        .windows(2)
+        .filter(|pair| is_consonant(pair[0]) && takes_inherent_vowel(pair[1]))
+        .count()
+}
+
+/// Returns whether an inherent vowel is read between a consonant and `next`,
+/// which is not a vowel sign, a hasanta, a nukta or a joiner.
+fn takes_inherent_vowel(next: char) -> bool {
+    !matches!(
+        next,
+        '\u{09BC}' | '\u{09BE}'..='\u{09CD}' | '\u{09D7}' | '\u{200C}' | '\u{200D}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inputs() {
+        let table = KeyTable::new(&PatternSet::avro());
+        assert_eq!(table.inherent_vowel.as_deref(), Some("o"));
+
+        let all = |word| table.inputs(word, |_| true, 64);
+        assert_eq!(all("আমি"), ["ami"]);
+        // The inherent vowel is typed between consonants
+        assert_eq!(all("কবিতা"), ["kobita", "qobita"]);
+        // The next best inputs when the best aren't accepted
+        let inputs = table.inputs("কবিতা", |input| input != "kobita", 64);
+        assert_eq!(inputs, ["qobita"]);
+        // The weighted patterns first, so ং isn't typed `n` and শ isn't typed
+        // `s`, and the least ambiguous ones, `ng` over `nn`
+        assert_eq!(all("বাংলা"), ["bangla"]);
+        assert_eq!(all("বাংলাদেশ"), ["bangladesh"]);
+        assert_eq!(all("শাক"), ["shak", "shaq"]);
+
+        // The hasanta is a common suffix, typed with no key
+        assert_eq!(all("ক্"), ["k", "q"]);
+
+        assert!(all("x").is_empty());
+        assert!(table.inputs("আমি", |_| false, 64).is_empty());
+    }
+}
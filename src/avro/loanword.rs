@@ -0,0 +1,81 @@
+use crate::fst::FstTree;
+
+/// Magic bytes at the start of a spelling table generated by `generate`.
+const MAGIC: &[u8; 4] = b"UPLW";
+
+/// The standard Bangla spellings of English loanwords, like কম্পিউটার for
+/// `computer`.
+///
+/// The English words are the keys of an FST map whose values point into a
+/// table of the spellings, packed as UTF-8 text after the magic bytes:
+///
+/// ```text
+/// "UPLW" | spellings
+/// ```
+///
+/// A value is the offset of the spellings of a word in the table in its high
+/// 32 bits and their length in the low 32 bits. A word with several spellings
+/// has them separated by newlines.
+pub(crate) struct Loanwords<D: AsRef<[u8]>> {
+    words: FstTree<D>,
+    spellings: D,
+}
+
+impl<D: AsRef<[u8]>> Loanwords<D> {
+    pub fn from_bytes(words: D, spellings: D) -> Loanwords<D> {
+        let bytes = spellings.as_ref();
+        assert!(
+            bytes.len() >= MAGIC.len() && &bytes[..4] == MAGIC,
+            "Invalid loanword spelling table"
+        );
+        assert!(
+            std::str::from_utf8(&bytes[4..]).is_ok(),
+            "Invalid loanword spelling table"
+        );
+
+        Loanwords {
+            words: FstTree::from_fst(words),
+            spellings,
+        }
+    }
+
+    /// Returns the Bangla spellings of the English `word`, ignoring its case.
+    pub fn get(&self, word: &str) -> impl Iterator<Item = &str> {
+        let span = if word.bytes().all(|b| b.is_ascii_alphabetic()) {
+            let value = self.words.get(&word.to_ascii_lowercase()).unwrap_or(0);
+            let offset = (value >> 32) as usize;
+            let len = (value & 0xFFFF_FFFF) as usize;
+
+            // The table is checked to be UTF-8 and the spans are at char
+            // boundaries
+            std::str::from_utf8(&self.spellings.as_ref()[offset..offset + len]).unwrap()
+        } else {
+            ""
+        };
+
+        span.split('\n').filter(|spelling| !spelling.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loanwords() {
+        let loanwords = Loanwords::from_bytes(
+            include_bytes!("loanwords.fst").as_slice(),
+            include_bytes!("loanwords.bin").as_slice(),
+        );
+
+        let get = |word| loanwords.get(word).collect::<Vec<_>>();
+
+        assert_eq!(get("computer"), ["কম্পিউটার"]);
+        assert_eq!(get("Facebook"), ["ফেসবুক"]);
+        assert_eq!(get("UNICEF"), ["ইউনিসেফ"]);
+        assert!(get("compute").is_empty());
+        assert!(get("amar").is_empty());
+        assert!(get("").is_empty());
+        assert!(get("face book").is_empty());
+    }
+}
@@ -1,2 +1,16 @@
+mod acronym;
+mod affix;
+mod autocorrect;
+#[cfg(feature = "english")]
+mod english;
+mod inverse;
+mod loanword;
+mod patterns;
+pub(crate) mod rule;
 mod suggest;
+mod table;
+pub use acronym::{is_acronym, spell_acronym};
+pub use affix::Affixes;
+pub use autocorrect::{Autocorrect, Correction};
+pub use patterns::{PatternError, PatternSet};
 pub use suggest::Suggest;
@@ -0,0 +1,394 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fmt,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    avro::{
+        rule::{Condition, Context},
+        table::{Block, BlockSource, BlockTable, ENTIRE_BLOCK_OPTIONAL},
+    },
+    fst::{encode, FstTree},
+};
+
+/// A block of the pattern source, whose transliterations are a regex.
+#[derive(Deserialize)]
+struct RegexBlock {
+    #[serde(flatten)]
+    alternatives: RegexAlternatives,
+    #[serde(default)]
+    rules: Vec<RegexRule>,
+}
+
+/// A rule of a block, used instead of it when its condition holds.
+#[derive(Deserialize)]
+struct RegexRule {
+    when: Condition,
+    #[serde(flatten)]
+    alternatives: RegexAlternatives,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RegexAlternatives {
+    transliterate: String,
+    entire_block_optional: Option<bool>,
+    #[serde(default)]
+    weights: HashMap<String, u64>,
+}
+
+impl RegexAlternatives {
+    /// Compiles the alternatives of `pattern` into a block with `flags` and
+    /// the rules `before`.
+    fn compile(
+        &self,
+        pattern: &str,
+        flags: u32,
+        before: &str,
+    ) -> Result<BlockSource, PatternError> {
+        let exploded =
+            rexplode::explode(&self.transliterate).map_err(|error| PatternError::Regex {
+                pattern: pattern.to_owned(),
+                message: error.to_string(),
+            })?;
+
+        if let Some(alternative) = self
+            .weights
+            .keys()
+            .find(|alternative| !exploded.contains(alternative))
+        {
+            return Err(PatternError::Weight {
+                pattern: pattern.to_owned(),
+                transliteration: alternative.clone(),
+            });
+        }
+
+        // Unique and non-empty transliterations only
+        let transliterations = exploded
+            .iter()
+            .filter(|transliteration| !transliteration.is_empty())
+            .map(|transliteration| {
+                let weight = self.weights.get(transliteration).copied().unwrap_or(0);
+                Ok((encode_str(pattern, transliteration)?, weight))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        let flags = if self.entire_block_optional.unwrap_or(false) {
+            flags | ENTIRE_BLOCK_OPTIONAL
+        } else {
+            flags
+        };
+
+        Ok(BlockSource {
+            flags,
+            before: before.to_owned(),
+            transliterations,
+            rules: Vec::new(),
+        })
+    }
+}
+
+/// The Roman patterns of a phonetic scheme, the Bangla transliterations of
+/// every pattern and the common suffixes (hasanta, phola etc.) tried after
+/// every pattern.
+///
+/// [`PatternSet::avro`] is the built-in Avro Phonetic scheme, used directly
+/// from the bytes embedded in the library. Other schemes can be compiled at
+/// runtime from their source with [`PatternSet::from_regex_json`].
+pub struct PatternSet {
+    /// The FST map from the patterns to their index in `blocks`.
+    pub(crate) patterns: FstTree<Cow<'static, [u8]>>,
+    pub(crate) blocks: BlockTable<Cow<'static, [u8]>>,
+    pub(crate) common_suffixes: Vec<Cow<'static, str>>,
+}
+
+impl PatternSet {
+    /// The Avro Phonetic patterns.
+    pub fn avro() -> Self {
+        let common_data = include_str!("../../data/source-common-patterns.txt");
+
+        PatternSet {
+            patterns: FstTree::from_fst(Cow::Borrowed(include_bytes!("patterns.fst"))),
+            blocks: BlockTable::from_bytes(
+                Cow::Borrowed(include_bytes!("blocks.bin")),
+                Cow::Borrowed(include_bytes!("blocks.fst")),
+            ),
+            common_suffixes: common_data.lines().map(Cow::Borrowed).collect(),
+        }
+    }
+
+    /// Compiles the patterns of a phonetic scheme from a source in the format
+    /// of `source-regex-patterns.json`, mapping every Roman pattern to a regex
+    /// of its transliterations:
+    ///
+    /// ```json
+    /// { "kh": { "transliterate": "(খ|(ক্ষ))" }, "o": { "transliterate": "([ওো]|(অ))?", "entireBlockOptional": true } }
+    /// ```
+    ///
+    /// A pattern may weigh its more common transliterations, which then rank
+    /// first, with `"weights": { "স": 2, "শ": 1 }`. The others weigh 0.
+    ///
+    /// A pattern may also have rules, blocks used instead of its own when
+    /// their condition holds. The first rule that holds is used:
+    ///
+    /// ```json
+    /// { "o": { "transliterate": "([ওো]|(অ))?", "entireBlockOptional": true, "rules": [{ "when": { "wordStart": true }, "transliterate": "([ওঅ])" }] } }
+    /// ```
+    ///
+    /// A condition holds at the start (`wordStart`) or the end (`wordEnd`) of
+    /// a word, after a Roman consonant or vowel (`"after": "consonant"` or
+    /// `"vowel"`) and before a key (`"before": "y"`), or any combination.
+    ///
+    /// The `common_suffixes` replace those of `source-common-patterns.txt`,
+    /// one per line.
+    pub fn from_regex_json(json: &str, common_suffixes: &str) -> Result<Self, PatternError> {
+        let source: HashMap<String, RegexBlock> =
+            serde_json::from_str(json).map_err(PatternError::Json)?;
+
+        // The patterns are sorted by their encoding, which is the order of
+        // the FST keys
+        let mut blocks = BTreeMap::new();
+
+        let mut count = 0;
+
+        for (pattern, block) in source {
+            let key = encode_str(&pattern, &pattern)?;
+
+            let mut compiled = block.alternatives.compile(&pattern, 0, "")?;
+            compiled.rules = block
+                .rules
+                .iter()
+                .map(|rule| {
+                    rule.alternatives
+                        .compile(&pattern, rule.when.flags(), &rule.when.before)
+                })
+                .collect::<Result<_, _>>()?;
+
+            count += 1 + compiled.rules.len();
+            blocks.insert(key, compiled);
+        }
+
+        if count > usize::from(u16::MAX) {
+            return Err(PatternError::TooManyPatterns(count));
+        }
+
+        let mut patterns = fst::MapBuilder::memory();
+        for (index, key) in blocks.keys().enumerate() {
+            patterns
+                .insert(key, index as u64)
+                .expect("The patterns are added in order");
+        }
+        let patterns = patterns
+            .into_inner()
+            .expect("Failed to build the pattern FST in memory");
+
+        let blocks = BlockTable::build(&blocks.into_values().collect::<Vec<_>>());
+
+        let common_suffixes = common_suffixes
+            .lines()
+            .map(|suffix| {
+                encode_str(suffix, suffix)?;
+                Ok(Cow::Owned(suffix.to_owned()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(PatternSet {
+            patterns: FstTree::from_fst(Cow::Owned(patterns)),
+            blocks,
+            common_suffixes,
+        })
+    }
+}
+
+impl PatternSet {
+    /// Splits the longest pattern off the start of `input`, returning it and
+    /// the rest of the input, or `None` if `input` doesn't start with a
+    /// pattern.
+    pub fn segment<'a>(&self, input: &'a str) -> Option<(&'a str, &'a str)> {
+        let (matched, remaining, complete) = self.patterns.match_longest_common_prefix(input);
+
+        if complete {
+            return Some((matched, remaining));
+        }
+
+        // The longest key prefix is not a pattern itself, so fall back to the
+        // longest pattern it starts with
+        (1..matched.len())
+            .rev()
+            .filter(|&i| input.is_char_boundary(i))
+            .find(|&i| self.patterns.get(&input[..i]).is_some())
+            .map(|i| input.split_at(i))
+    }
+
+    /// Returns the block of `pattern` to use in `context`.
+    pub(crate) fn block_in(
+        &self,
+        pattern: &str,
+        context: &Context<'_>,
+    ) -> Option<Block<'_, Cow<'static, [u8]>>> {
+        self.patterns
+            .get(pattern)
+            .and_then(|index| self.blocks.block_in(index, context))
+    }
+
+    /// Returns whether the block of `pattern` may match nothing, like the
+    /// inherent vowel typed with `o` in Avro Phonetic.
+    pub(crate) fn is_optional(&self, pattern: &str) -> bool {
+        self.patterns
+            .get(pattern)
+            .and_then(|index| self.blocks.block(index))
+            .is_some_and(|block| block.entire_block_optional())
+    }
+
+    /// Maps every transliteration of the patterns to the patterns giving it
+    /// and its weight for them. The transliterations of the rules are left
+    /// out.
+    pub(crate) fn reverse(&self) -> HashMap<String, Vec<(String, u64)>> {
+        let patterns: HashMap<u64, String> = self
+            .patterns
+            .entries(0)
+            .into_iter()
+            .map(|(_, pattern, index)| (index, pattern))
+            .collect();
+
+        let mut reverse: HashMap<_, Vec<_>> = HashMap::new();
+        for (index, transliteration, weight) in self.blocks.transliterations() {
+            if let Some(pattern) = patterns.get(&index) {
+                reverse
+                    .entry(transliteration)
+                    .or_default()
+                    .push((pattern.clone(), weight));
+            }
+        }
+
+        reverse
+    }
+}
+
+impl Default for PatternSet {
+    fn default() -> Self {
+        Self::avro()
+    }
+}
+
+/// Encodes `text` of `pattern` for the FSTs.
+fn encode_str(pattern: &str, text: &str) -> Result<Vec<u8>, PatternError> {
+    text.chars()
+        .map(|character| {
+            encode(character).ok_or_else(|| PatternError::Character {
+                pattern: pattern.to_owned(),
+                character,
+            })
+        })
+        .collect()
+}
+
+/// An error in the source of a [`PatternSet`].
+#[derive(Debug)]
+pub enum PatternError {
+    /// The source is not valid JSON in the expected format.
+    Json(serde_json::Error),
+    /// The transliterations of a pattern are not a valid regex.
+    Regex { pattern: String, message: String },
+    /// A pattern or its transliterations contain a character which is not
+    /// Bengali, printable ASCII, ZWNJ or ZWJ.
+    Character { pattern: String, character: char },
+    /// A weight is given to a string which is not a transliteration of the
+    /// pattern.
+    Weight {
+        pattern: String,
+        transliteration: String,
+    },
+    /// There are more patterns and rules than can be indexed.
+    TooManyPatterns(usize),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::Json(error) => write!(f, "invalid pattern source: {error}"),
+            PatternError::Regex { pattern, message } => {
+                write!(f, "invalid transliterations of `{pattern}`: {message}")
+            }
+            PatternError::Character { pattern, character } => {
+                write!(f, "unsupported character {character:?} in `{pattern}`")
+            }
+            PatternError::Weight {
+                pattern,
+                transliteration,
+            } => write!(
+                f,
+                "`{transliteration}` is not a transliteration of `{pattern}`"
+            ),
+            PatternError::TooManyPatterns(count) => write!(f, "too many patterns: {count}"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PatternError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_avro_patterns() {
+        let compiled = PatternSet::from_regex_json(
+            include_str!("../../data/source-regex-patterns.json"),
+            include_str!("../../data/source-common-patterns.txt"),
+        )
+        .unwrap();
+        let avro = PatternSet::avro();
+
+        // The same tables as the ones made by `generate`
+        assert_eq!(compiled.patterns.as_bytes(), avro.patterns.as_bytes());
+        assert_eq!(compiled.blocks.as_bytes(), avro.blocks.as_bytes());
+        assert_eq!(compiled.common_suffixes, avro.common_suffixes);
+    }
+
+    #[test]
+    fn test_pattern_errors() {
+        let error = |json| PatternSet::from_regex_json(json, "").err().unwrap();
+
+        assert!(matches!(error("[]"), PatternError::Json(_)));
+        assert!(matches!(
+            error(r#"{ "k": { "transliterate": "(ক" } }"#),
+            PatternError::Regex { pattern, .. } if pattern == "k"
+        ));
+        assert!(matches!(
+            error(r#"{ "k": { "transliterate": "क" } }"#),
+            PatternError::Character {
+                character: 'क', ..
+            }
+        ));
+        assert!(matches!(
+            error(r#"{ "é": { "transliterate": "এ" } }"#),
+            PatternError::Character {
+                character: 'é', ..
+            }
+        ));
+
+        assert!(matches!(
+            error(r#"{ "k": { "transliterate": "[কখ]", "weights": { "গ": 1 } } }"#),
+            PatternError::Weight { transliteration, .. } if transliteration == "গ"
+        ));
+        assert!(PatternSet::from_regex_json(
+            r#"{ "k": { "transliterate": "[কখ]", "weights": { "ক": 1 } } }"#,
+            ""
+        )
+        .is_ok());
+
+        assert!(PatternSet::from_regex_json("{}", "্").is_ok());
+        assert!(PatternSet::from_regex_json("{}", "\t").is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use serde::Deserialize;
+
+/// Rule flag set when the rule applies only at the start of a word.
+pub(crate) const WORD_START: u32 = 1 << 1;
+/// Rule flag set when the rule applies only at the end of a word.
+pub(crate) const WORD_END: u32 = 1 << 2;
+/// Rule flag set when the rule applies only after a consonant.
+pub(crate) const AFTER_CONSONANT: u32 = 1 << 3;
+/// Rule flag set when the rule applies only after a vowel.
+pub(crate) const AFTER_VOWEL: u32 = 1 << 4;
+
+/// What comes before a pattern in a word.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Preceding {
+    /// Nothing, the pattern starts the word.
+    Nothing,
+    Consonant,
+    Vowel,
+    /// A digit or a symbol.
+    Other,
+}
+
+impl Preceding {
+    /// Classifies the Roman character `c` typed before a pattern.
+    pub fn roman(c: char) -> Self {
+        match c.to_ascii_lowercase() {
+            'a' | 'e' | 'i' | 'o' | 'u' => Preceding::Vowel,
+            c if c.is_ascii_alphabetic() => Preceding::Consonant,
+            _ => Preceding::Other,
+        }
+    }
+
+    /// Classifies the Bangla character `c` ending a word that is continued
+    /// with a pattern.
+    pub fn bangla(c: char) -> Self {
+        match c {
+            // Independent vowels and vowel signs
+            '\u{0985}'..='\u{0994}' | '\u{09BE}'..='\u{09CC}' | '\u{09E0}'..='\u{09E3}' => {
+                Preceding::Vowel
+            }
+            // Consonants, signs like anusvara, nukta and hasanta
+            '\u{0981}'..='\u{0983}'
+            | '\u{0995}'..='\u{09B9}'
+            | '\u{09BC}'
+            | '\u{09CD}'
+            | '\u{09CE}'
+            | '\u{09DC}'..='\u{09DF}' => Preceding::Consonant,
+            _ => Preceding::Other,
+        }
+    }
+}
+
+/// The surroundings of a pattern matched in the input, on which the rules of
+/// its block depend.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Context<'a> {
+    pub preceding: Preceding,
+    /// The input left after the pattern.
+    pub following: &'a str,
+}
+
+impl Context<'_> {
+    /// Returns whether a rule with `flags`, which applies only before the key
+    /// `before` unless it is empty, holds in this context.
+    pub fn holds(&self, flags: u32, before: &str) -> bool {
+        let holds = |flag, condition| flags & flag == 0 || condition;
+
+        holds(WORD_START, self.preceding == Preceding::Nothing)
+            && holds(WORD_END, self.following.is_empty())
+            && holds(AFTER_CONSONANT, self.preceding == Preceding::Consonant)
+            && holds(AFTER_VOWEL, self.preceding == Preceding::Vowel)
+            && self.following.starts_with(before)
+    }
+}
+
+/// The condition of a rule in the pattern source, like
+/// `{ "wordStart": true, "after": "consonant", "before": "y" }`. Every
+/// condition given must hold.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct Condition {
+    #[serde(default)]
+    word_start: bool,
+    #[serde(default)]
+    word_end: bool,
+    after: Option<After>,
+    /// The key the rest of the input starts with.
+    #[serde(default)]
+    pub before: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum After {
+    Consonant,
+    Vowel,
+}
+
+impl Condition {
+    /// The rule flags of the condition.
+    pub fn flags(&self) -> u32 {
+        let mut flags = 0;
+
+        if self.word_start {
+            flags |= WORD_START;
+        }
+        if self.word_end {
+            flags |= WORD_END;
+        }
+        match self.after {
+            Some(After::Consonant) => flags |= AFTER_CONSONANT,
+            Some(After::Vowel) => flags |= AFTER_VOWEL,
+            None => {}
+        }
+
+        flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conditions() {
+        let context = |preceding, following| Context {
+            preceding,
+            following,
+        };
+        let flags = |json| serde_json::from_str::<Condition>(json).unwrap().flags();
+
+        let start = context(Preceding::Nothing, "mi");
+        assert!(start.holds(0, ""));
+        assert!(start.holds(flags(r#"{ "wordStart": true }"#), ""));
+        assert!(!start.holds(flags(r#"{ "wordEnd": true }"#), ""));
+        assert!(!start.holds(flags(r#"{ "after": "vowel" }"#), ""));
+        assert!(start.holds(0, "m"));
+        assert!(!start.holds(0, "n"));
+
+        let end = context(Preceding::roman('k'), "");
+        assert!(end.holds(flags(r#"{ "wordEnd": true, "after": "consonant" }"#), ""));
+        assert!(!end.holds(flags(r#"{ "wordStart": true, "wordEnd": true }"#), ""));
+        assert!(!end.holds(0, "y"));
+
+        assert_eq!(Preceding::roman('E'), Preceding::Vowel);
+        assert_eq!(Preceding::roman('`'), Preceding::Other);
+        assert_eq!(Preceding::bangla('ক'), Preceding::Consonant);
+        assert_eq!(Preceding::bangla('\u{09CD}'), Preceding::Consonant);
+        assert_eq!(Preceding::bangla('া'), Preceding::Vowel);
+        assert_eq!(Preceding::bangla('আ'), Preceding::Vowel);
+        assert_eq!(Preceding::bangla('১'), Preceding::Other);
+
+        assert!(serde_json::from_str::<Condition>(r#"{ "after": "digit" }"#).is_err());
+        assert!(serde_json::from_str::<Condition>(r#"{ "wordstart": true }"#).is_err());
+    }
+}
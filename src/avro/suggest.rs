@@ -1,154 +1,413 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+
+use once_cell::sync::{Lazy, OnceCell};
+
+#[cfg(feature = "english")]
+use crate::avro::english::EnglishWords;
+use crate::{
+    avro::{
+        acronym::spell_acronym,
+        autocorrect::{Autocorrect, Correction},
+        inverse::KeyTable,
+        loanword::Loanwords,
+        rule::Preceding,
+        Affixes, PatternSet,
+    },
+    complete,
+    fst::FstArena,
+    numerals,
+    scheme::{matching_nodes, ranked, Scheme},
+    segment, Budget, CompletionOptions, Suggestions, WORDS,
+};
+
+/// The Bangla spellings of English loanwords.
+static LOANWORDS: Lazy<Loanwords<&[u8]>> = Lazy::new(|| {
+    Loanwords::from_bytes(
+        include_bytes!("loanwords.fst"),
+        include_bytes!("loanwords.bin"),
+    )
+});
+
+/// The number of inputs checked by [`Suggest::keys_for`].
+const MAX_INPUTS_TRIED: usize = 64;
+
+/// The English words, for suggesting them as typed.
+#[cfg(feature = "english")]
+static ENGLISH: Lazy<EnglishWords<&[u8]>> =
+    Lazy::new(|| EnglishWords::from_fst(include_bytes!("english.fst")));
 
-use once_cell::sync::Lazy;
-use serde::Deserialize;
-
-use crate::{fst::FstTree, WORDS};
-
-static PATTERNS: Lazy<FstTree<&[u8]>> =
-    Lazy::new(|| FstTree::from_fst(include_bytes!("patterns.fst")));
-
-#[derive(Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Block {
-    pub transliterate: Vec<String>,
-    pub entire_block_optional: Option<bool>,
+pub struct Suggest {
+    patterns: PatternSet,
+    autocorrect: Autocorrect,
+    /// The patterns typing every transliteration, built on the first call to
+    /// [`Suggest::keys_for`].
+    keys: OnceCell<KeyTable>,
+    #[cfg(feature = "english")]
+    english: bool,
 }
 
-pub struct Suggest {
-    patterns: HashMap<String, Block>,
-    common_suffixes: Vec<&'static str>,
+impl Default for Suggest {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Suggest {
     pub fn new() -> Self {
-        let patterns_data = include_bytes!("../../data/preprocessed-patterns.json");
-        let common_data = include_str!("../../data/source-common-patterns.txt");
-
-        let patterns: HashMap<String, Block> = serde_json::from_slice(patterns_data).unwrap();
-        let common_suffixes = common_data.lines().collect();
-
         Suggest {
-            patterns,
-            common_suffixes,
+            patterns: PatternSet::avro(),
+            autocorrect: Autocorrect::default(),
+            keys: OnceCell::new(),
+            #[cfg(feature = "english")]
+            english: false,
         }
     }
 
+    /// Uses the `patterns` of another phonetic scheme instead of the Avro
+    /// Phonetic ones.
+    pub fn with_patterns(mut self, patterns: PatternSet) -> Self {
+        self.patterns = patterns;
+        self.keys = OnceCell::new();
+        self
+    }
+
+    /// Uses the `autocorrect` table, whose corrections of an input are
+    /// suggested before the words transliterated from it.
+    pub fn with_autocorrect(mut self, autocorrect: Autocorrect) -> Self {
+        self.autocorrect = autocorrect;
+        self
+    }
+
+    /// Suggests the English word typed after its transliterations when
+    /// `enabled`, e.g. `hello` for `hello`, so that English can be typed
+    /// without switching the keyboard mode.
+    #[cfg(feature = "english")]
+    pub fn with_english_words(mut self, enabled: bool) -> Self {
+        self.english = enabled;
+        self
+    }
+
     pub fn suggest(&self, input: &str) -> Vec<String> {
+        self.suggest_with_budget(input, &Budget::default()).words
+    }
+
+    /// Like [`Suggest::suggest`], but stops walking the dictionary when
+    /// `budget` runs out. The suggestions are then flagged as truncated: the
+    /// corrections, loanwords and English word are kept, but only the
+    /// transliterations matching the whole input by then.
+    pub fn suggest_with_budget(&self, input: &str, budget: &Budget) -> Suggestions {
         let words = Lazy::force(&WORDS);
-        let patterns = Lazy::force(&PATTERNS);
-        let input = fix_string(input);
+        let mut arena = words.arena(budget);
+
+        let preferred = self.preferred_words(&mut arena, input);
+
+        let root = arena.root();
+        let matched_nodes = matching_nodes(
+            self,
+            &mut arena,
+            root,
+            &fix_string(input),
+            Preceding::Nothing,
+        );
+
+        // Every node stands for a distinct word, so there are no duplicates left
+        let words = ranked(matched_nodes)
+            .into_iter()
+            .filter_map(|n| arena.get_word(n));
 
-        let (matched, mut remaining, _) = patterns.match_longest_common_prefix(&input);
+        let mut words = with_preferred(preferred, words);
+        words.extend(self.english_word(input));
 
-        let matched_patterns = if let Some(block) = self.patterns.get(matched) {
-            &block.transliterate
+        Suggestions {
+            words,
+            truncated: arena.exhausted(),
+        }
+    }
+
+    /// Like [`Suggest::suggest`], but also completes the words matching the
+    /// input with the dictionary words starting with them, e.g. `bist` gives
+    /// বিস্তারিত.
+    ///
+    /// The words matching the whole input are ranked before the completions
+    /// when [`CompletionOptions::include_exact_match`] is set. Unless
+    /// [`CompletionOptions::order`] sorts them, the completions are ranked like
+    /// the words they complete. The number of
+    /// grapheme clusters added to each matching word is bounded by
+    /// [`CompletionOptions::depth`], which by default depends on the number of
+    /// grapheme clusters of the best matching word, so that a single letter
+    /// isn't completed into a large part of the dictionary.
+    pub fn suggest_completions(&self, input: &str, options: &CompletionOptions) -> Suggestions {
+        let words = Lazy::force(&WORDS);
+        let mut arena = words.arena(&options.budget);
+
+        let preferred = self.preferred_words(&mut arena, input);
+
+        let root = arena.root();
+        let scored_nodes = matching_nodes(
+            self,
+            &mut arena,
+            root,
+            &fix_string(input),
+            Preceding::Nothing,
+        );
+        let matched_nodes = ranked(scored_nodes.clone());
+
+        let depth = matched_nodes.first().map_or(0, |&node| {
+            options.depth_for(segment::count_clusters(&arena.path(node)))
+        });
+
+        // The completions are ranked by the score of the word they complete
+        let mut completions = Vec::new();
+        let mut reached = Vec::new();
+        for &(node, score) in &scored_nodes {
+            reached.clear();
+            let last = arena.last_char(node);
+            complete::complete(&mut arena, node, last, depth, &mut reached);
+            completions.extend(reached.iter().map(|&completion| (completion, score)));
+        }
+
+        // A completion of several matching words keeps its best score, and a
+        // matching word may be the completion of a shorter one
+        completions.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+        completions.dedup_by_key(|&mut (node, _)| node);
+
+        let mut completions: Vec<_> = completions
+            .into_iter()
+            .filter(|(node, _)| {
+                scored_nodes
+                    .binary_search_by_key(node, |&(n, _)| n)
+                    .is_err()
+            })
+            .filter_map(|(node, score)| Some((Reverse(score), arena.get_word(node)?)))
+            .collect();
+        completions.sort_unstable();
+
+        let mut words = Vec::new();
+
+        if options.include_exact_match {
+            words =
+                complete::collect_words(&arena, &matched_nodes, options.order, options.max_results);
+        }
+
+        let max_results = options.max_results.map(|max| max - words.len());
+        words.extend(complete::order_words(
+            completions.into_iter().map(|(_, word)| word),
+            options.order,
+            max_results,
+        ));
+
+        let mut words = with_preferred(preferred, words);
+
+        if let Some(max) = options.max_results {
+            words.truncate(max);
+        }
+
+        Suggestions {
+            words,
+            truncated: arena.exhausted(),
+        }
+    }
+
+    /// Suggests the words starting with the Bangla `prefix` already typed and
+    /// continuing with the Roman `input`, e.g. `বাং` and `la` give বাংলা, so
+    /// that a committed word can be edited by typing more keys.
+    pub fn suggest_after(&self, prefix: &str, input: &str) -> Vec<String> {
+        self.suggest_after_with_budget(prefix, input, &Budget::default())
+            .words
+    }
+
+    /// Like [`Suggest::suggest_after`], but stops when `budget` runs out,
+    /// flagging the words continuing `prefix` with the whole input by then as
+    /// truncated. There are none if the prefix itself wasn't reached.
+    pub fn suggest_after_with_budget(
+        &self,
+        prefix: &str,
+        input: &str,
+        budget: &Budget,
+    ) -> Suggestions {
+        let words = Lazy::force(&WORDS);
+        let mut arena = words.arena(budget);
+
+        let Some(node) = arena.matching_node(prefix) else {
+            return Suggestions {
+                words: Vec::new(),
+                truncated: arena.exhausted(),
+            };
+        };
+
+        // The input continues the word, so its first pattern follows the last
+        // letter of the prefix
+        let input = fix_string(input);
+        let preceding = prefix
+            .chars()
+            .last()
+            .map_or(Preceding::Nothing, Preceding::bangla);
+
+        let matched_nodes = if input.is_empty() {
+            vec![node]
         } else {
-            return vec![];
+            ranked(matching_nodes(self, &mut arena, node, &input, preceding))
         };
 
-        let mut matched_nodes = matched_patterns
-            .iter()
-            .filter_map(|p| words.matching_node(p))
-            .collect::<Vec<_>>();
+        Suggestions {
+            words: matched_nodes
+                .into_iter()
+                .filter_map(|n| arena.get_word(n))
+                .collect(),
+            truncated: arena.exhausted(),
+        }
+    }
 
-        let additional_nodes = matched_nodes
-            .iter()
-            .flat_map(|node| {
-                self.common_suffixes
-                    .iter()
-                    .filter_map(|suffix| node.get_matching_node(suffix))
-            })
-            .collect::<Vec<_>>();
-
-        matched_nodes.extend(additional_nodes);
-
-        while !remaining.is_empty() {
-            let (mut new_matched, new_remaining, mut complete) =
-                patterns.match_longest_common_prefix(remaining);
-
-            if !complete {
-                for i in (0..remaining.len()).rev() {
-                    (new_matched, _, complete) =
-                        patterns.match_longest_common_prefix(&remaining[..i]);
-
-                    if complete {
-                        remaining = &remaining[i..];
-                        break;
-                    }
-                }
-            } else {
-                remaining = new_remaining;
+    /// Returns the words suggested before the transliterations of `input`:
+    /// its corrections in the autocorrect table, its spellings as an English
+    /// loanword, and its Bengali forms for a number or its spelling for an
+    /// acronym.
+    fn preferred_words<D: AsRef<[u8]>>(
+        &self,
+        arena: &mut FstArena<'_, D>,
+        input: &str,
+    ) -> Vec<String> {
+        let mut words = match self.autocorrect.get(input) {
+            None => Vec::new(),
+            Some(Correction::Bangla(word)) => vec![word.to_owned()],
+            Some(Correction::Roman(spelling)) => {
+                let root = arena.root();
+
+                ranked(matching_nodes(
+                    self,
+                    arena,
+                    root,
+                    &fix_string(spelling),
+                    Preceding::Nothing,
+                ))
+                .into_iter()
+                .filter_map(|n| arena.get_word(n))
+                .collect()
             }
+        };
 
-            let new_matched_patterns = if let Some(block) = self.patterns.get(new_matched) {
-                &block.transliterate
-            } else {
-                // If no patterns match, we can stop here
-                break;
-            };
+        let loanwords = Lazy::force(&LOANWORDS).get(input.trim()).map(str::to_owned);
+        let acronym = spell_acronym(input);
 
-            let new_matched_nodes = new_matched_patterns
-                .iter()
-                .flat_map(|p| {
-                    matched_nodes
-                        .iter()
-                        .filter_map(|node| node.get_matching_node(p))
-                })
-                .collect::<Vec<_>>();
-
-            if self
-                .patterns
-                .get(new_matched)
-                .map_or(false, |v| v.entire_block_optional.is_some())
-            {
-                // Entirely optional patterns like "([ওোঅ]|(অ্য)|(য়ো?))?" may not yield any result
-                matched_nodes.extend(new_matched_nodes);
-            } else {
-                matched_nodes = new_matched_nodes;
+        for word in loanwords.chain(numerals::suggest(input)).chain(acronym) {
+            if !words.contains(&word) {
+                words.push(word);
             }
+        }
 
-            let additional_matched_nodes = matched_nodes
-                .iter()
-                .flat_map(|node| {
-                    self.common_suffixes
-                        .iter()
-                        .filter_map(|suffix| node.get_matching_node(suffix))
-                })
-                .collect::<Vec<_>>();
-            matched_nodes.extend(additional_matched_nodes);
+        words
+    }
+
+    /// Returns the English word typed, if the English words are suggested and
+    /// `input` is one of them.
+    #[cfg(feature = "english")]
+    fn english_word(&self, input: &str) -> Option<String> {
+        if !self.english {
+            return None;
+        }
+
+        Lazy::force(&ENGLISH).get(input.trim())
+    }
+
+    #[cfg(not(feature = "english"))]
+    fn english_word(&self, _input: &str) -> Option<String> {
+        None
+    }
+
+    /// Like [`Suggest::suggest`], but punctuation typed around the word is
+    /// kept on every suggestion instead of being dropped, e.g. `(ami),`
+    /// gives `(আমি),`.
+    ///
+    /// With `convert_punctuation`, the punctuation is converted the Avro way
+    /// (`$` to `৳`, and a single `.` after the word to `।`).
+    ///
+    /// The input is a single word: the punctuation inside it is dropped, so
+    /// `ami,tumi.` is looked up as `amitumi`.
+    pub fn suggest_with_affixes(&self, input: &str, convert_punctuation: bool) -> Vec<String> {
+        let affixes = Affixes::split(input);
+
+        if affixes.core.is_empty() {
+            if affixes.prefix.is_empty() {
+                return vec![];
+            }
+
+            return vec![affixes.wrap("", convert_punctuation)];
         }
 
-        let suggestions: HashSet<_> = matched_nodes
+        self.suggest(affixes.core)
             .into_iter()
-            .filter_map(|n| n.get_word())
-            .collect();
-        suggestions.into_iter().collect()
+            .map(|word| affixes.wrap(&word, convert_punctuation))
+            .collect()
+    }
+
+    /// Returns the most natural Roman inputs suggesting the Bangla `word`,
+    /// e.g. `kobita` for কবিতা.
+    ///
+    /// The inputs are found by walking the transliterations of the patterns
+    /// back to their keys. They type the inherent vowels between consonants
+    /// and the phola, with the patterns weighted for the letters and the
+    /// fewest keys. Every input is checked with [`Suggest::suggest`], trying
+    /// up to 64 inputs until some suggest the word. Returns nothing if the
+    /// word can't be typed.
+    pub fn keys_for(&self, word: &str) -> Vec<String> {
+        let table = self.keys.get_or_init(|| KeyTable::new(&self.patterns));
+
+        table.inputs(
+            word,
+            |input| self.suggest(input).iter().any(|w| w == word),
+            MAX_INPUTS_TRIED,
+        )
     }
 }
 
-fn fix_string(s: &str) -> String {
-    let s = s.trim();
-
-    let mut result = String::new();
-    let mut prev = ' '; // prev is non-alphabetic at first
-    for c in s.chars() {
-        // Fix string for o. In the beginning, after punctuations etc it should be capital O
-        if (c == 'o' || c == 'O') && !prev.is_ascii_alphabetic() {
-            result.push('O');
-        } else if c.is_ascii_alphanumeric() || c == '`' {
-            result.push(c.to_ascii_lowercase());
-        }
+/// The Avro Phonetic scheme, or the scheme of the patterns given with
+/// [`Suggest::with_patterns`].
+impl Scheme for Suggest {
+    fn normalize(&self, input: &str) -> String {
+        fix_string(input)
+    }
+
+    fn patterns(&self) -> &PatternSet {
+        &self.patterns
+    }
+
+    /// Suggests like [`Suggest::suggest_with_budget`], with the autocorrect,
+    /// loanword and other preferred words.
+    fn suggest_with_budget(&self, input: &str, budget: &Budget) -> Suggestions {
+        Suggest::suggest_with_budget(self, input, budget)
+    }
+}
+
+/// Puts the `preferred` words before the other `words`.
+fn with_preferred(
+    mut preferred: Vec<String>,
+    words: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let count = preferred.len();
 
-        prev = c;
+    for word in words {
+        if !preferred[..count].contains(&word) {
+            preferred.push(word);
+        }
     }
 
-    result
+    preferred
+}
+
+/// Keeps the letters, digits and backticks of `s`, lowercased, which are the
+/// keys of the patterns.
+fn fix_string(s: &str) -> String {
+    s.trim()
+        .chars()
+        .filter(|&c| c.is_ascii_alphanumeric() || c == '`')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::Order;
 
     fn sort(mut vec: Vec<String>) -> Vec<String> {
         vec.sort();
@@ -157,13 +416,34 @@ mod tests {
 
     #[test]
     fn test_fix_string() {
-        assert_eq!(fix_string("o"), "O");
-        assert_eq!(fix_string("o!"), "O");
-        assert_eq!(fix_string("o!o"), "OO");
-        assert_eq!(fix_string("osomapto"), "Osomapto");
+        assert_eq!(fix_string("o"), "o");
+        assert_eq!(fix_string("o!"), "o");
+        assert_eq!(fix_string("o!o"), "oo");
+        assert_eq!(fix_string("Osomapto"), "osomapto");
         assert_eq!(fix_string("6t``"), "6t``");
     }
 
+    #[test]
+    fn test_scheme() {
+        let suggest = Suggest::new();
+        let scheme: &dyn Scheme = &suggest;
+
+        assert_eq!(scheme.suggest("amra"), suggest.suggest("amra"));
+        assert_eq!(scheme.suggest("42")[0], "৪২");
+    }
+
+    #[test]
+    fn test_segment() {
+        let suggest = Suggest::new();
+
+        assert_eq!(
+            suggest.segment(&suggest.normalize("Kompiuter")),
+            (vec!["k", "o", "mp", "i", "u", "t", "e", "r"], "")
+        );
+        assert_eq!(suggest.segment("sh"), (vec!["sh"], ""));
+        assert_eq!(suggest.segment(""), (vec![], ""));
+    }
+
     #[test]
     fn test_suggestions() {
         let suggest = Suggest::new();
@@ -212,6 +492,396 @@ mod tests {
         assert_eq!(sort(suggest.suggest("shesh")), vec!["শেষ", "সেস"]);
     }
 
+    #[test]
+    fn test_suggestions_with_affixes() {
+        let suggest = Suggest::new();
+
+        assert_eq!(
+            sort(suggest.suggest_with_affixes("(amra),", false)),
+            vec!["(অমরা),", "(আমরা),", "(আমড়া),"]
+        );
+        assert_eq!(
+            sort(suggest.suggest_with_affixes("\"apni.", true)),
+            vec!["\"আপনি।"]
+        );
+        assert_eq!(suggest.suggest_with_affixes("$", true), vec!["৳"]);
+        // Only a single full stop after the word is converted
+        assert_eq!(
+            suggest.suggest_with_affixes("...ami!!", true),
+            suggest.suggest_with_affixes("...ami!!", false)
+        );
+        assert!(suggest
+            .suggest_with_affixes("ami...", true)
+            .contains(&"আমি...".to_owned()));
+        // The punctuation inside the word is dropped
+        assert_eq!(
+            suggest.suggest_with_affixes("ban,gla.", false),
+            suggest.suggest_with_affixes("bangla.", false)
+        );
+        assert!(suggest
+            .suggest_with_affixes("ban,gla.", false)
+            .contains(&"বাংলা.".to_owned()));
+        assert_eq!(
+            suggest.suggest_with_affixes(" ", true),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_completions() {
+        let suggest = Suggest::new();
+
+        let options = CompletionOptions::default();
+        let result = suggest.suggest_completions("bist", &options);
+        assert!(!result.truncated);
+        assert!(result.words.contains(&"বিস্তারিত".to_string()));
+        assert!(!suggest.suggest("bist").contains(&"বিস্তারিত".to_string()));
+
+        // The exact matches come first
+        let options = CompletionOptions {
+            include_exact_match: true,
+            order: Order::Alphabetical,
+            ..Default::default()
+        };
+        let exact = sort(suggest.suggest("ami"));
+        let result = suggest.suggest_completions("ami", &options);
+        assert_eq!(result.words[..exact.len()], exact);
+        assert_eq!(
+            sort(result.words[exact.len()..].to_vec()),
+            result.words[exact.len()..]
+        );
+        assert!(result.words[exact.len()..].contains(&"আমির".to_string()));
+        assert!(result.words[exact.len()..]
+            .iter()
+            .all(|w| !exact.contains(w)));
+
+        let options = CompletionOptions {
+            include_exact_match: true,
+            order: Order::ShortestFirst,
+            max_results: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest.suggest_completions("kompi", &options).words,
+            ["কম্পিত", "কম্পিতা", "কম্পিউটার"]
+        );
+
+        // The completions of the best matching words come first, so that
+        // `max_results` keeps them
+        let all = suggest
+            .suggest_completions("sari", &CompletionOptions::default())
+            .words;
+        let options = CompletionOptions {
+            max_results: Some(8),
+            ..Default::default()
+        };
+        let best = suggest.suggest_completions("sari", &options).words;
+        assert_eq!(best, all[..8]);
+        assert!(best.iter().all(|w| w.starts_with("সারি")));
+        assert!(all.iter().any(|w| w.starts_with("শারি")));
+
+        let options = CompletionOptions {
+            depth: Some(0),
+            include_exact_match: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            sort(suggest.suggest_completions("ami", &options).words),
+            exact
+        );
+
+        assert!(suggest
+            .suggest_completions("", &CompletionOptions::default())
+            .words
+            .is_empty());
+
+        let options = CompletionOptions {
+            budget: Budget {
+                max_nodes: Some(20),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(suggest.suggest_completions("bist", &options).truncated);
+    }
+
+    #[test]
+    fn test_suggestions_after_prefix() {
+        let suggest = Suggest::new();
+
+        assert_eq!(suggest.suggest_after("বাং", "la"), ["বাংলা"]);
+        assert_eq!(suggest.suggest_after("আম", "i"), ["আমি"]);
+        assert_eq!(suggest.suggest_after("কম্পি", "uTar"), ["কম্পিউটার"]);
+        assert_eq!(sort(suggest.suggest_after("বি", "starit")), ["বিস্তারিত"]);
+
+        // An `o` continuing a word is a kar
+        assert!(suggest.suggest_after("ক", "o").contains(&"কো".to_string()));
+
+        assert_eq!(suggest.suggest_after("আমি", ""), ["আমি"]);
+        assert!(suggest.suggest_after("কম্পি", "").is_empty());
+        assert_eq!(
+            sort(suggest.suggest_after("", "ami")),
+            sort(suggest.suggest("ami"))
+        );
+        assert!(suggest.suggest_after("xyz", "a").is_empty());
+
+        let budget = Budget {
+            max_nodes: Some(2),
+            ..Default::default()
+        };
+        let result = suggest.suggest_after_with_budget("কম্পি", "uTar", &budget);
+        assert!(result.truncated);
+        assert!(result.words.is_empty());
+    }
+
+    #[test]
+    fn test_numbers() {
+        let suggest = Suggest::new();
+
+        assert_eq!(suggest.suggest("123"), ["১২৩", "একশ তেইশ"]);
+        assert_eq!(suggest.suggest("1"), ["১", "এক"]);
+        assert_eq!(suggest.suggest("2nd"), ["২য়", "দ্বিতীয়"]);
+
+        let words = suggest.suggest("1st");
+        assert_eq!(words[..2], ["১ম", "প্রথম"]);
+        assert!(words.contains(&"একশত".to_string()));
+
+        assert_eq!(suggest.suggest_with_affixes("(42),", false)[0], "(৪২),");
+    }
+
+    #[test]
+    fn test_acronyms() {
+        let suggest = Suggest::new();
+
+        assert_eq!(suggest.suggest("BBC")[0], "বিবিসি");
+        assert_eq!(suggest.suggest_with_affixes("(BBC)", false)[0], "(বিবিসি)");
+        assert!(!suggest.suggest("bbc").contains(&"বিবিসি".to_string()));
+        assert_eq!(suggest.suggest("T"), Suggest::new().suggest("t"));
+    }
+
+    #[test]
+    fn test_loanwords() {
+        let suggest = Suggest::new();
+
+        assert_eq!(suggest.suggest("computer")[0], "কম্পিউটার");
+        assert_eq!(suggest.suggest("facebook")[0], "ফেসবুক");
+        assert_eq!(
+            suggest
+                .suggest("computer")
+                .iter()
+                .filter(|w| *w == "কম্পিউটার")
+                .count(),
+            1
+        );
+
+        // An acronym which is a loanword is spelled as a word first
+        let words = suggest.suggest("UNICEF");
+        assert_eq!(words[..2], ["ইউনিসেফ", "ইউএনআইসিইএফ"]);
+    }
+
+    #[cfg(feature = "english")]
+    #[test]
+    fn test_english_words() {
+        let suggest = Suggest::new().with_english_words(true);
+
+        let words = suggest.suggest("hello");
+        assert_eq!(words.last().unwrap(), "hello");
+        assert_eq!(words[..words.len() - 1], Suggest::new().suggest("hello"));
+
+        assert_eq!(suggest.suggest("google").last().unwrap(), "Google");
+        assert_eq!(suggest.suggest("Facebook").last().unwrap(), "Facebook");
+        assert_eq!(suggest.suggest("ami"), Suggest::new().suggest("ami"));
+        assert_eq!(
+            suggest
+                .suggest_with_affixes("(hello),", false)
+                .last()
+                .unwrap(),
+            "(hello),"
+        );
+
+        assert!(!Suggest::new()
+            .with_english_words(false)
+            .suggest("hello")
+            .contains(&"hello".to_string()));
+    }
+
+    #[test]
+    fn test_weighted_transliterations() {
+        let suggest = Suggest::new();
+
+        // The more common transliteration of each pattern ranks first
+        assert_eq!(suggest.suggest("tin"), ["তিন", "টিন", "তিঙ", "টিং"]);
+        assert_eq!(suggest.suggest("sar")[0], "সার");
+        assert_eq!(suggest.suggest("put")[0], "পুত");
+
+        // The weights are given with the patterns
+        let patterns = PatternSet::from_regex_json(
+            r#"{ "s": { "transliterate": "[সশ]", "weights": { "শ": 1 } }, "ap": { "transliterate": "াপ" } }"#,
+            "",
+        )
+        .unwrap();
+        let suggest = Suggest::new().with_patterns(patterns);
+        assert_eq!(suggest.suggest("sap"), ["শাপ", "সাপ"]);
+    }
+
+    #[test]
+    fn test_pattern_rules() {
+        let suggest = Suggest::new();
+
+        // Vowels at the start of a word are independent ones
+        assert_eq!(suggest.suggest("oikko"), ["ঐক্য"]);
+        assert_eq!(suggest.suggest("ouShodh"), ["ঔষধ"]);
+        assert_eq!(sort(suggest.suggest("orko")), ["অর্ক", "ওরক"]);
+        assert_eq!(suggest.suggest("mohon"), ["মোহন"]);
+
+        let patterns = PatternSet::from_regex_json(
+            r#"{
+                "k": { "transliterate": "ক" },
+                "l": { "transliterate": "ল" },
+                "m": { "transliterate": "ম" },
+                "a": { "transliterate": "া", "rules": [{ "when": { "wordStart": true }, "transliterate": "আ" }] },
+                "i": { "transliterate": "ই", "rules": [
+                    { "when": { "after": "consonant", "wordEnd": true }, "transliterate": "ি" },
+                    { "when": { "after": "consonant", "before": "l" }, "transliterate": "ি" }
+                ] }
+            }"#,
+            "",
+        )
+        .unwrap();
+        let suggest = Suggest::new().with_patterns(patterns);
+
+        assert_eq!(suggest.suggest("am"), ["আম"]);
+        assert_eq!(suggest.suggest("kam"), ["কাম"]);
+        assert_eq!(suggest.suggest("ma"), ["মা"]);
+        assert_eq!(suggest.suggest("kali"), ["কালি"]);
+        assert_eq!(suggest.suggest("kil"), ["কিল"]);
+        assert!(suggest.suggest("kim").is_empty());
+
+        // The prefix being continued is the context of the input
+        assert_eq!(suggest.suggest_after("আ", "mi"), ["আমি"]);
+        assert_eq!(suggest.suggest_after("ক", "am"), ["কাম"]);
+        assert!(suggest.suggest_after("", "kam").contains(&"কাম".to_owned()));
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let patterns = PatternSet::from_regex_json(
+            r#"{
+                "a": { "transliterate": "(আ|া)" },
+                "m": { "transliterate": "ম" },
+                "r": { "transliterate": "র" }
+            }"#,
+            "্",
+        )
+        .unwrap();
+        let suggest = Suggest::new().with_patterns(patterns);
+
+        assert_eq!(suggest.suggest("ama"), ["আমা"]);
+        assert_eq!(sort(suggest.suggest("amar")), ["আমার"]);
+        // Without a pattern for `i`, the input stops matching there
+        assert_eq!(suggest.suggest("ami"), suggest.suggest("am"));
+
+        let avro = PatternSet::from_regex_json(
+            include_str!("../../data/source-regex-patterns.json"),
+            include_str!("../../data/source-common-patterns.txt"),
+        )
+        .unwrap();
+        let suggest = Suggest::new().with_patterns(avro);
+
+        for input in ["ami", "bistari", "arO", "kompiuTar"] {
+            assert_eq!(suggest.suggest(input), Suggest::new().suggest(input));
+        }
+    }
+
+    #[test]
+    fn test_autocorrect() {
+        let autocorrect =
+            Autocorrect::from_json(r#"{ "bd": "বাংলাদেশ", "aamar": "amar", "ami": "আমি" }"#).unwrap();
+        let suggest = Suggest::new().with_autocorrect(autocorrect);
+
+        assert_eq!(suggest.suggest("bd")[0], "বাংলাদেশ");
+        assert_eq!(
+            sort(suggest.suggest("aamar")[..2].to_vec()),
+            sort(Suggest::new().suggest("amar"))
+        );
+
+        // A correction is not suggested twice
+        let words = suggest.suggest("ami");
+        assert_eq!(words[0], "আমি");
+        assert_eq!(words.iter().filter(|w| *w == "আমি").count(), 1);
+        assert_eq!(sort(words), sort(Suggest::new().suggest("ami")));
+
+        assert_eq!(suggest.suggest_with_affixes("(bd)", false)[0], "(বাংলাদেশ)");
+        assert_eq!(suggest.suggest("bdx"), Suggest::new().suggest("bdx"));
+
+        let options = CompletionOptions {
+            max_results: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest.suggest_completions("bd", &options).words,
+            ["বাংলাদেশ"]
+        );
+    }
+
+    #[test]
+    fn test_suggestions_with_budget() {
+        let suggest = Suggest::new();
+
+        let result = suggest.suggest_with_budget("sari", &Budget::default());
+        assert!(!result.truncated);
+        assert_eq!(sort(result.words), sort(suggest.suggest("sari")));
+
+        // Long ambiguous inputs stop at the node limit
+        let input = "chchhngkshngkkhshshngjhththchchhngkshngkkhshshngjhthth";
+        let budget = Budget {
+            max_nodes: Some(5),
+            ..Default::default()
+        };
+        let result = suggest.suggest_with_budget(input, &budget);
+        assert!(result.truncated);
+        assert!(result.words.is_empty());
+
+        // The words found are still suggestions for the whole input
+        let input = "bishshobiddaloy";
+        let all = suggest.suggest(input);
+        for max_nodes in [10, 30, 50] {
+            let budget = Budget {
+                max_nodes: Some(max_nodes),
+                ..Default::default()
+            };
+            let result = suggest.suggest_with_budget(input, &budget);
+            assert!(result.truncated);
+            assert!(result.words.iter().all(|word| all.contains(word)));
+        }
+
+        let budget = Budget {
+            max_nodes: Some(1000),
+            ..Default::default()
+        };
+        let result = suggest.suggest_with_budget(input, &budget);
+        assert!(!result.truncated);
+        assert_eq!(result.words, all);
+
+        let budget = Budget {
+            deadline: Some(std::time::Instant::now()),
+            ..Default::default()
+        };
+        let result = suggest.suggest_with_budget("a".repeat(1000).as_str(), &budget);
+        assert!(result.truncated);
+        assert!(result.words.is_empty());
+
+        let token = crate::CancelToken::new();
+        token.cancel();
+        let budget = Budget {
+            cancel: Some(token),
+            ..Default::default()
+        };
+        let result = suggest.suggest_with_budget("amra", &budget);
+        assert!(result.truncated);
+        assert!(result.words.is_empty());
+    }
+
     #[test]
     fn test_empty_suggestion() {
         let suggest = Suggest::new();
@@ -220,4 +890,41 @@ mod tests {
         assert_eq!(suggest.suggest("6t`"), Vec::<String>::new());
         assert_eq!(suggest.suggest("t6th"), Vec::<String>::new());
     }
+
+    #[test]
+    fn test_keys_for() {
+        let suggest = Suggest::new();
+
+        // The most natural spelling first
+        assert_eq!(suggest.keys_for("আমি")[0], "ami");
+        assert_eq!(suggest.keys_for("কবিতা")[0], "kobita");
+        assert_eq!(suggest.keys_for("বিস্তারিত")[0], "bistarit");
+        assert_eq!(suggest.keys_for("দুঃখ")[0], "duhkh");
+        assert_eq!(suggest.keys_for("শা\u{09DC}ি")[0], "shari");
+        assert_eq!(suggest.keys_for("কম্পিউটার")[0], "kompiutar");
+        assert_eq!(suggest.keys_for("কই")[0], "koi");
+        assert_eq!(suggest.keys_for("সংবাদ")[0], "songbad");
+
+        // Only the most natural inputs, not the ones with extra or fallback
+        // keys which still suggest the word
+        assert_eq!(suggest.keys_for("বাংলাদেশ"), ["bangladesh"]);
+        assert_eq!(suggest.keys_for("কবিতা"), ["kobita", "qobita"]);
+        let keys = suggest.keys_for("বিশ্ববিদ্যাল\u{09DF}");
+        assert!(!keys.is_empty());
+        assert!(keys.iter().all(|k| !k.starts_with("bisho")));
+
+        // Every input suggests the word
+        for word in ["ক্ষমা", "কৃষ্ণ", "কম্পিউটার", "বাংলাদেশ"]
+        {
+            let keys = suggest.keys_for(word);
+            assert!(!keys.is_empty(), "{word}");
+            assert!(keys.len() <= MAX_INPUTS_TRIED);
+            assert!(keys
+                .iter()
+                .all(|k| suggest.suggest(k).contains(&word.to_owned())));
+        }
+
+        assert!(suggest.keys_for("").is_empty());
+        assert!(suggest.keys_for("abc").is_empty());
+    }
 }
@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    avro::rule::Context,
+    fst::{FstAutomaton, FstTree},
+};
+
+/// Magic bytes at the start of a block table generated by `generate`.
+const MAGIC: &[u8; 4] = b"UPBT";
+
+/// Size of the fixed header: magic and block count.
+const HEADER_LEN: usize = 8;
+
+/// Size of the record of a block: five `u32`s.
+const RECORD_LEN: usize = 20;
+
+/// Block flag set when the entire block is optional, like `(...)?`.
+pub(crate) const ENTIRE_BLOCK_OPTIONAL: u32 = 1;
+
+/// The pattern blocks, which are used directly from their bytes.
+///
+/// The blocks are packed in a table of fixed size records followed by the
+/// keys of their rules as UTF-8 text (all integers are little endian
+/// `u32`s):
+///
+/// ```text
+/// "UPBT" | block count B | records[B] | keys
+/// record = flags | first rule | rule count | key offset | key length
+/// ```
+///
+/// A block may have rules, conditional blocks used instead of it when their
+/// condition holds, like the transliterations of a vowel at the start of a
+/// word. The flags of a rule hold its condition (see [`crate::avro::rule`])
+/// and its key, if any, is the one the input must continue with. The blocks
+/// of the patterns come first, in the order of the patterns, followed by the
+/// rules of every pattern in the same order.
+///
+/// The transliterations of every block are stored in a single FST map, each
+/// prefixed with the block index as a big endian `u16`. So the transliterations
+/// of a block are the sub-automaton reached by walking its index. The value of
+/// a transliteration is its weight, which ranks the more common
+/// transliterations of a pattern first.
+pub(crate) struct BlockTable<D: AsRef<[u8]>> {
+    records: D,
+    blocks: usize,
+    transliterations: FstTree<D>,
+}
+
+/// A block to add to a [`BlockTable`].
+pub(crate) struct BlockSource {
+    pub flags: u32,
+    /// The key the input must continue with for a rule to hold.
+    pub before: String,
+    /// The encoded transliterations with their weights.
+    pub transliterations: BTreeMap<Vec<u8>, u64>,
+    /// The rules of a pattern block, tried in order. Rules have no rules.
+    pub rules: Vec<BlockSource>,
+}
+
+impl<D: AsRef<[u8]> + From<Vec<u8>>> BlockTable<D> {
+    /// Builds the table of the pattern `blocks` and their rules. There must be
+    /// at most `u16::MAX` blocks, rules included.
+    pub fn build(blocks: &[BlockSource]) -> Self {
+        let rules = blocks.iter().flat_map(|block| &block.rules);
+        let all = blocks.iter().chain(rules).collect::<Vec<_>>();
+
+        let mut records = MAGIC.to_vec();
+        records.extend((all.len() as u32).to_le_bytes());
+
+        let mut keys = String::new();
+        let mut first_rule = blocks.len();
+
+        for block in &all {
+            let rule_count = block.rules.len();
+            let record = [
+                block.flags,
+                if rule_count > 0 { first_rule as u32 } else { 0 },
+                rule_count as u32,
+                keys.len() as u32,
+                block.before.len() as u32,
+            ];
+            records.extend(record.iter().flat_map(|field| field.to_le_bytes()));
+
+            first_rule += rule_count;
+            keys.push_str(&block.before);
+        }
+
+        records.extend(keys.as_bytes());
+
+        let mut transliterations = fst::raw::Builder::memory();
+
+        for (index, block) in all.iter().enumerate() {
+            for (transliteration, &weight) in &block.transliterations {
+                let mut key = (index as u16).to_be_bytes().to_vec();
+                key.extend(transliteration);
+                transliterations
+                    .insert(key, weight)
+                    .expect("The keys are added in order");
+            }
+        }
+
+        let transliterations = transliterations
+            .into_inner()
+            .expect("Failed to build the block FST in memory");
+
+        BlockTable::from_bytes(records.into(), transliterations.into())
+    }
+}
+
+impl<D: AsRef<[u8]>> BlockTable<D> {
+    pub fn from_bytes(records: D, transliterations: D) -> BlockTable<D> {
+        let bytes = records.as_ref();
+        assert!(
+            bytes.len() >= HEADER_LEN && &bytes[..4] == MAGIC,
+            "Invalid block table"
+        );
+
+        let blocks = read_u32(bytes, 4) as usize;
+        let keys = HEADER_LEN + blocks * RECORD_LEN;
+        assert!(
+            bytes.len() >= keys && std::str::from_utf8(&bytes[keys..]).is_ok(),
+            "Invalid block table"
+        );
+
+        let transliterations = FstTree::from_fst(transliterations);
+
+        BlockTable {
+            records,
+            blocks,
+            transliterations,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn as_bytes(&self) -> (&[u8], &[u8]) {
+        (self.records.as_ref(), self.transliterations.as_bytes())
+    }
+
+    /// Returns the block at `index`, regardless of its rules.
+    pub fn block(&self, index: u64) -> Option<Block<'_, D>> {
+        let index = u16::try_from(index)
+            .ok()
+            .filter(|&i| usize::from(i) < self.blocks)?;
+
+        let automaton = self.transliterations.automaton(&index.to_be_bytes())?;
+
+        Some(Block {
+            flags: self.field(usize::from(index), 0),
+            automaton,
+        })
+    }
+
+    /// Returns the first rule of the block at `index` that holds in
+    /// `context`, or the block itself if none does.
+    pub fn block_in(&self, index: u64, context: &Context<'_>) -> Option<Block<'_, D>> {
+        let block = usize::try_from(index).ok().filter(|&i| i < self.blocks)?;
+        let first_rule = self.field(block, 1) as usize;
+        let rule_count = self.field(block, 2) as usize;
+
+        let rule = (first_rule..first_rule + rule_count)
+            .filter(|&rule| rule < self.blocks)
+            .find(|&rule| context.holds(self.field(rule, 0), self.key(rule)));
+
+        self.block(rule.map_or(index, |rule| rule as u64))
+    }
+
+    /// Returns the index, transliteration and weight of every transliteration
+    /// of the blocks.
+    pub fn transliterations(&self) -> impl Iterator<Item = (u64, String, u64)> {
+        self.transliterations
+            .entries(2)
+            .into_iter()
+            .map(|(index, transliteration, weight)| {
+                let index = u16::from_be_bytes([index[0], index[1]]);
+                (u64::from(index), transliteration, weight)
+            })
+    }
+
+    /// Reads the field `field` of the record of `block`.
+    fn field(&self, block: usize, field: usize) -> u32 {
+        read_u32(
+            self.records.as_ref(),
+            HEADER_LEN + block * RECORD_LEN + field * 4,
+        )
+    }
+
+    /// Returns the key of the rule `block`.
+    fn key(&self, block: usize) -> &str {
+        let keys = &self.records.as_ref()[HEADER_LEN + self.blocks * RECORD_LEN..];
+        let offset = self.field(block, 3) as usize;
+        let len = self.field(block, 4) as usize;
+
+        // The keys are checked to be UTF-8 and the spans are at char
+        // boundaries
+        std::str::from_utf8(&keys[offset..offset + len]).unwrap()
+    }
+}
+
+/// A block of transliteration alternatives for a single pattern.
+pub(crate) struct Block<'a, D: AsRef<[u8]>> {
+    flags: u32,
+    automaton: FstAutomaton<'a, D>,
+}
+
+impl<'a, D: AsRef<[u8]>> Block<'a, D> {
+    pub fn entire_block_optional(&self) -> bool {
+        self.flags & ENTIRE_BLOCK_OPTIONAL != 0
+    }
+
+    /// The automaton matching the transliterations of the block, weighted.
+    pub fn automaton(&self) -> &FstAutomaton<'a, D> {
+        &self.automaton
+    }
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use fst::Automaton;
+
+    use super::*;
+    use crate::{avro::rule::Preceding, fst::SparseAutomaton};
+
+    fn walk<'a>(
+        automaton: &FstAutomaton<'a, &[u8]>,
+        input: &str,
+    ) -> <FstAutomaton<'a, &'a [u8]> as Automaton>::State {
+        let mut state = automaton.start();
+        for c in input.chars() {
+            state = automaton.accept(&state, c as u8);
+        }
+        state
+    }
+
+    fn matches(automaton: &FstAutomaton<&[u8]>, input: &str) -> bool {
+        automaton.is_match(&walk(automaton, input))
+    }
+
+    fn weight(automaton: &FstAutomaton<&[u8]>, input: &str) -> u64 {
+        automaton.weight(&walk(automaton, input))
+    }
+
+    #[test]
+    fn test_block_table() {
+        let table = BlockTable::from_bytes(
+            include_bytes!("blocks.bin").as_slice(),
+            include_bytes!("blocks.fst").as_slice(),
+        );
+        let patterns = crate::fst::FstTree::from_fst(include_bytes!("patterns.fst").as_slice());
+
+        let block = table.block(patterns.get("1").unwrap()).unwrap();
+        assert!(!block.entire_block_optional());
+        assert!(matches(block.automaton(), "1"));
+        assert!(matches(block.automaton(), "এক"));
+        assert!(matches(block.automaton(), "১"));
+        assert!(!matches(block.automaton(), "এ"));
+        assert!(!matches(block.automaton(), "২"));
+
+        let block = table.block(patterns.get("o").unwrap()).unwrap();
+        assert!(block.entire_block_optional());
+        assert!(matches(block.automaton(), "\u{09DF}\u{09CB}"));
+
+        // An `o` starting a word isn't optional
+        let start = Context {
+            preceding: Preceding::Nothing,
+            following: "rko",
+        };
+        let block = table.block_in(patterns.get("o").unwrap(), &start).unwrap();
+        assert!(!block.entire_block_optional());
+        assert!(matches(block.automaton(), "ও"));
+
+        let middle = Context {
+            preceding: Preceding::roman('m'),
+            following: "hon",
+        };
+        let block = table.block_in(patterns.get("o").unwrap(), &middle).unwrap();
+        assert!(block.entire_block_optional());
+
+        // The more common transliterations weigh more
+        let block = table.block(patterns.get("s").unwrap()).unwrap();
+        assert_eq!(weight(block.automaton(), "স"), 2);
+        assert_eq!(weight(block.automaton(), "শ"), 1);
+        assert_eq!(weight(block.automaton(), "ষ"), 0);
+
+        assert!(table.block(u64::MAX).is_none());
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use super::sanitize::nukta_form;
+use crate::{
+    layouts::{longest_match, reorder_pre_base_kars},
+    segment::is_consonant,
+    CompletionOptions, Suggestions,
+};
+
+const HASANTA: char = '\u{09CD}';
+const NUKTA: char = '\u{09BC}';
+const REPH: &str = "র্";
+/// The Bijoy character of the reph, typed after the consonant it is on.
+const BIJOY_REPH: char = '©';
+
+/// The characters of the Bijoy (SutonnyMJ) font and the Bangla text they
+/// show. When several characters show the same text, the first is the one
+/// written by [`from_unicode`].
+const TABLE: &[(&str, &str)] = &[
+    // Vowels
+    ("Av", "আ"),
+    ("A", "অ"),
+    ("B", "ই"),
+    ("C", "ঈ"),
+    ("D", "উ"),
+    ("E", "ঊ"),
+    ("F", "ঋ"),
+    ("G", "এ"),
+    ("H", "ঐ"),
+    ("I", "ও"),
+    ("J", "ঔ"),
+    // Consonants
+    ("K", "ক"),
+    ("L", "খ"),
+    ("M", "গ"),
+    ("N", "ঘ"),
+    ("O", "ঙ"),
+    ("P", "চ"),
+    ("Q", "ছ"),
+    ("R", "জ"),
+    ("S", "ঝ"),
+    ("T", "ঞ"),
+    ("U", "ট"),
+    ("V", "ঠ"),
+    ("W", "ড"),
+    ("X", "ঢ"),
+    ("Y", "ণ"),
+    ("Z", "ত"),
+    ("_", "থ"),
+    ("`", "দ"),
+    ("a", "ধ"),
+    ("b", "ন"),
+    ("c", "প"),
+    ("d", "ফ"),
+    ("e", "ব"),
+    ("f", "ভ"),
+    ("g", "ম"),
+    ("h", "য"),
+    ("i", "র"),
+    ("j", "ল"),
+    ("k", "শ"),
+    ("l", "ষ"),
+    ("m", "স"),
+    ("n", "হ"),
+    ("o", "\u{09DC}"),
+    ("p", "\u{09DD}"),
+    ("q", "\u{09DF}"),
+    ("r", "ৎ"),
+    // Signs
+    ("s", "ং"),
+    ("t", "ঃ"),
+    ("u", "ঁ"),
+    ("&", "্"),
+    ("|", "।"),
+    // Vowel signs
+    ("v", "া"),
+    ("w", "ি"),
+    ("x", "ী"),
+    ("y", "ু"),
+    ("z", "ু"),
+    ("“", "ু"),
+    ("–", "ু"),
+    ("æ", "ু"),
+    ("~", "ূ"),
+    ("ƒ", "ূ"),
+    ("‚", "ূ"),
+    ("„", "ৃ"),
+    ("…", "ৃ"),
+    ("‡", "ে"),
+    ("†", "ে"),
+    ("‰", "ৈ"),
+    ("ˆ", "ৈ"),
+    ("Š", "ৗ"),
+    // Phalas and the half forms of the conjuncts
+    ("¨", "্য"),
+    ("Ö", "্র"),
+    ("ª", "্র"),
+    ("«", "্র"),
+    ("¬", "্ল"),
+    ("ø", "্ল"),
+    ("¡", "্ব"),
+    ("Ÿ", "্ব"),
+    ("^", "্ব"),
+    ("¦", "্ব"),
+    ("¥", "্ম"),
+    ("§", "্ম"),
+    ("œ", "্ন"),
+    ("¢", "্ভ"),
+    ("£", "্ভ্র"),
+    ("‹", "্ক"),
+    ("Œ", "্ক্র"),
+    ("—", "্ত"),
+    ("Í", "্ত"),
+    ("‘", "্তু"),
+    ("’", "্থ"),
+    ("¿", "্ত্র"),
+    ("è", "্ণ"),
+    ("ú", "্প"),
+    ("¯", "স্"),
+    ("®", "ষ্"),
+    ("¤", "ম্"),
+    ("•", "ঙ্"),
+    ("”", "চ্"),
+    ("˜", "দ্"),
+    ("™", "দ্"),
+    ("š", "ন্"),
+    ("›", "ন্"),
+    // Conjuncts
+    ("°", "ক্ক"),
+    ("±", "ক্ট"),
+    ("³", "ক্ত"),
+    ("µ", "ক্র"),
+    ("¶", "ক্ষ"),
+    ("·", "ক্স"),
+    ("¸", "গু"),
+    ("»", "গ্ধ"),
+    ("¼", "ঙ্ক"),
+    ("½", "ঙ্গ"),
+    ("¾", "জ্জ"),
+    ("À", "জ্ঝ"),
+    ("Á", "জ্ঞ"),
+    ("Â", "ঞ্চ"),
+    ("Ã", "ঞ্ছ"),
+    ("Ä", "ঞ্জ"),
+    ("Å", "ঞ্ঝ"),
+    ("Æ", "ট্ট"),
+    ("Ç", "ড্ড"),
+    ("È", "ণ্ট"),
+    ("É", "ণ্ঠ"),
+    ("Ê", "ণ্ড"),
+    ("Ë", "ত্ত"),
+    ("Ì", "ত্থ"),
+    ("Î", "ন্ত্ব"),
+    ("Ï", "দ্দ"),
+    ("×", "দ্ধ"),
+    ("Ø", "দ্ব"),
+    ("Ù", "দ্ম"),
+    ("Ú", "ন্ঠ"),
+    ("Û", "ন্ড"),
+    ("Ü", "ন্ধ"),
+    ("Ý", "ন্স"),
+    ("Þ", "প্ট"),
+    ("ß", "প্ত"),
+    ("à", "প্প"),
+    ("á", "প্স"),
+    ("â", "ব্জ"),
+    ("ã", "ব্দ"),
+    ("ä", "ব্ধ"),
+    ("å", "ভ্র"),
+    ("ç", "ম্ফ"),
+    ("é", "ল্ক"),
+    ("ê", "ল্গ"),
+    ("ë", "ল্ট"),
+    ("ì", "ল্ড"),
+    ("í", "ল্প"),
+    ("î", "ল্ফ"),
+    ("ï", "শু"),
+    ("ð", "শ্চ"),
+    ("ó", "ষ্ট"),
+    ("ô", "ষ্ঠ"),
+    ("ò", "ষ্ণ"),
+    ("õ", "ষ্ফ"),
+    ("÷", "স্ট"),
+    ("ö", "স্খ"),
+    ("ù", "স্ফ"),
+    ("û", "হু"),
+    ("ü", "হৃ"),
+    ("ý", "হ্ন"),
+    ("þ", "হ্ম"),
+    // Digits
+    ("0", "০"),
+    ("1", "১"),
+    ("2", "২"),
+    ("3", "৩"),
+    ("4", "৪"),
+    ("5", "৫"),
+    ("6", "৬"),
+    ("7", "৭"),
+    ("8", "৮"),
+    ("9", "৯"),
+];
+
+/// A map between the two sides of [`TABLE`], with the number of characters
+/// of its longest key.
+struct Mapping {
+    map: HashMap<&'static str, &'static str>,
+    longest: usize,
+}
+
+impl Mapping {
+    fn new(pairs: impl Iterator<Item = (&'static str, &'static str)>) -> Self {
+        let mut map = HashMap::new();
+
+        for (from, to) in pairs {
+            map.entry(from).or_insert(to);
+        }
+        let longest = map.keys().map(|key| key.chars().count()).max().unwrap_or(0);
+
+        Mapping { map, longest }
+    }
+
+    /// Maps `text` from the longest key at every point, keeping the
+    /// characters which start no key.
+    ///
+    /// A half form followed by a phala, like the `স্` and `্ব` of স্ব, share
+    /// their hasanta.
+    fn map(&self, text: &str, mapped: &mut String) {
+        let mut remaining = text;
+
+        while let Some(first) = remaining.chars().next() {
+            match longest_match(remaining, &self.map, self.longest) {
+                Some((end, to)) => {
+                    match to.strip_prefix(HASANTA) {
+                        Some(phala) if mapped.ends_with(HASANTA) => mapped.push_str(phala),
+                        _ => mapped.push_str(to),
+                    }
+                    remaining = &remaining[end..];
+                }
+                None => {
+                    mapped.push(first);
+                    remaining = &remaining[first.len_utf8()..];
+                }
+            }
+        }
+    }
+}
+
+static TO_UNICODE: Lazy<Mapping> = Lazy::new(|| Mapping::new(TABLE.iter().copied()));
+static FROM_UNICODE: Lazy<Mapping> =
+    Lazy::new(|| Mapping::new(TABLE.iter().map(|&(bijoy, unicode)| (unicode, bijoy))));
+
+/// Converts the Bijoy (SutonnyMJ) encoded `text` to Unicode.
+///
+/// Bijoy text is in visual order: the pre-base kars (ি, ে and ৈ) come
+/// before their consonant and the reph after it. They are moved to their
+/// place in Unicode, and an e-kar around a consonant with an aa-kar or the
+/// au length mark makes the o-kar or the au-kar.
+pub fn to_unicode(text: &str) -> String {
+    let mut converted = String::new();
+
+    for (i, part) in text.split(BIJOY_REPH).enumerate() {
+        if i > 0 {
+            insert_reph(&mut converted);
+        }
+        TO_UNICODE.map(part, &mut converted);
+    }
+
+    reorder_pre_base_kars(&converted, false)
+}
+
+/// Inserts the reph before the consonant or conjunct ending `text`, which
+/// may be followed by its vowel signs.
+fn insert_reph(text: &mut String) {
+    let mut start = text.len();
+    let mut chars = text.char_indices().rev().peekable();
+
+    // The signs after the consonant
+    while let Some(&(i, c)) = chars.peek() {
+        if !is_post_base_sign(c) {
+            break;
+        }
+        start = i;
+        chars.next();
+    }
+
+    // The consonants joined by hasantas
+    let mut cluster_start = None;
+    while let Some((i, c)) = chars.next() {
+        if c == NUKTA {
+            continue;
+        }
+        if !is_consonant(c) {
+            break;
+        }
+        cluster_start = Some(i);
+
+        if chars.peek().map(|&(_, c)| c) != Some(HASANTA) {
+            break;
+        }
+        chars.next();
+    }
+
+    text.insert_str(cluster_start.unwrap_or(start), REPH);
+}
+
+fn is_post_base_sign(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0981}'..='\u{0983}' | '\u{09BE}' | '\u{09C0}'..='\u{09C4}' | '\u{09D7}'
+    )
+}
+
+/// Converts the Unicode Bangla `text` to the Bijoy (SutonnyMJ) encoding,
+/// in its visual order.
+pub fn from_unicode(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut converted = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !is_consonant(chars[i]) {
+            let start = i;
+            i += 1;
+            // Keep the stray signs with their letter, like the aa-kar of আ
+            while i < chars.len() && !is_consonant(chars[i]) {
+                i += 1;
+            }
+            FROM_UNICODE.map(&chars[start..i].iter().collect::<String>(), &mut converted);
+            continue;
+        }
+
+        let is_joined = |i: usize| {
+            chars.get(i) == Some(&HASANTA) && chars.get(i + 1).is_some_and(|&c| is_consonant(c))
+        };
+
+        let reph = chars[i] == 'র' && is_joined(i + 1);
+        if reph {
+            i += 2;
+        }
+
+        // The consonants joined by hasantas, with their nuktas
+        let mut cluster = String::new();
+        loop {
+            cluster.push(chars[i]);
+            i += 1;
+            if chars.get(i) == Some(&NUKTA) {
+                // ড, ঢ and য with a nukta have characters of their own
+                match nukta_form(chars[i - 1]) {
+                    Some(composed) => {
+                        cluster.pop();
+                        cluster.push(composed);
+                    }
+                    None => cluster.push(NUKTA),
+                }
+                i += 1;
+            }
+            if !is_joined(i) {
+                break;
+            }
+            cluster.push(HASANTA);
+            i += 1;
+        }
+
+        let mut pre_base = "";
+        let mut post_base = String::new();
+        while let Some(&c) = chars.get(i) {
+            match c {
+                'ি' => pre_base = "w",
+                'ে' => pre_base = "‡",
+                'ৈ' => pre_base = "‰",
+                'ো' => {
+                    pre_base = "‡";
+                    post_base.push('া');
+                }
+                'ৌ' => {
+                    pre_base = "‡";
+                    post_base.push('ৗ');
+                }
+                c if is_post_base_sign(c) => post_base.push(c),
+                _ => break,
+            }
+            i += 1;
+        }
+
+        converted.push_str(pre_base);
+        FROM_UNICODE.map(&cluster, &mut converted);
+        FROM_UNICODE.map(&post_base, &mut converted);
+        if reph {
+            converted.push(BIJOY_REPH);
+        }
+    }
+
+    converted
+}
+
+/// Suggests the dictionary words, in Unicode, completing the partially typed
+/// Bijoy encoded `word`.
+pub fn suggest(word: &str) -> Vec<String> {
+    super::suggest(&to_unicode(word))
+}
+
+/// Like [`suggest`], but completes with the words allowed by `options`.
+pub fn suggest_with(word: &str, options: &CompletionOptions) -> Suggestions {
+    super::suggest_with(&to_unicode(word), options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_unicode() {
+        assert_eq!(to_unicode("Avwg"), "আমি");
+        assert_eq!(to_unicode("evsjv"), "বাংলা");
+        assert_eq!(to_unicode("‡`k"), "দেশ");
+        assert_eq!(to_unicode("‡Kvb"), "কোন");
+        assert_eq!(to_unicode("‡MŠi"), "গৌর");
+        assert_eq!(to_unicode("wk¶v"), "শিক্ষা");
+        assert_eq!(to_unicode("¯^vaxbZv"), "স্বাধীনতা");
+        assert_eq!(to_unicode("Kw¤úDUvi"), "কম্পিউটার");
+        assert_eq!(to_unicode("e¨vsK"), "ব্যাংক");
+        assert_eq!(to_unicode("cÖkœ"), "প্রশ্ন");
+        // The reph after its consonant and vowel signs
+        assert_eq!(to_unicode("ag©"), "ধর্ম");
+        assert_eq!(to_unicode("Kg©x"), "কর্মী");
+        assert_eq!(to_unicode("Kgx©"), "কর্মী");
+        assert_eq!(to_unicode("K‡g©"), "কর্মে");
+        assert_eq!(to_unicode("Kvh©"), "কার্য");
+        assert_eq!(to_unicode("Avcbvi 12 wU eB|"), "আপনার ১২ টি বই।");
+    }
+
+    #[test]
+    fn test_from_unicode() {
+        assert_eq!(from_unicode("আমি"), "Avwg");
+        assert_eq!(from_unicode("দেশ"), "‡`k");
+        assert_eq!(from_unicode("কোন"), "‡Kvb");
+        assert_eq!(from_unicode("শিক্ষা"), "wk¶v");
+        assert_eq!(from_unicode("ধর্ম"), "ag©");
+        assert_eq!(from_unicode("কর্মী"), "Kgx©");
+        assert_eq!(from_unicode("আপনার ১২ টি বই।"), "Avcbvi 12 wU eB|");
+        // The letters with a nukta, precomposed or not
+        assert_eq!(from_unicode("হ\u{09DF}"), "nq");
+        assert_eq!(from_unicode("হয\u{09BC}"), "nq");
+        assert_eq!(from_unicode("পা\u{09DC}া"), "cvov");
+        assert_eq!(from_unicode("পাড\u{09BC}া"), "cvov");
+        assert_eq!(from_unicode("আষা\u{09DD}"), "Avlvp");
+        assert_eq!(from_unicode("আষাঢ\u{09BC}"), "Avlvp");
+
+        for word in [
+            "বাংলা",
+            "গৌর",
+            "স্বাধীনতা",
+            "কম্পিউটার",
+            "ব্যাংক",
+            "কার্যক্রম",
+            "ক্ষুদ্র",
+            "উজ্জ্বল",
+            "সূর্যোদ\u{09DF}",
+            "পা\u{09DC}া",
+        ] {
+            assert_eq!(to_unicode(&from_unicode(word)), word);
+        }
+    }
+
+    #[test]
+    fn test_suggest() {
+        assert!(suggest("Avgv").contains(&"আমার".to_owned()));
+        assert!(suggest("‡`").contains(&"দেশ".to_owned()));
+        assert!(suggest("xyz").is_empty());
+
+        let options = CompletionOptions {
+            include_exact_match: true,
+            order: crate::Order::Alphabetical,
+            max_results: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(suggest_with("Kg©", &options).words, ["কর্ম", "কর্মঅভিজ্ঞতা"]);
+    }
+}
@@ -1,61 +1,72 @@
-use std::collections::HashSet;
+use std::borrow::Cow;
 
 use once_cell::sync::Lazy;
 
-use crate::WORDS;
+use crate::{complete, segment, Budget, Suggestions, WORDS};
 
-const CHARS: [char; 61] = [
-    'অ', 'আ', 'ই', 'ঈ', 'উ', 'ঊ', 'ঋ', 'এ', 'ঐ', 'ও', 'ঔ', 'া', 'ি', 'ী', 'ু', 'ূ', 'ৃ', 'ে', 'ৈ', 'ো',
-    'ৌ', 'ক', 'খ', 'গ', 'ঘ', 'ঙ', 'চ', 'ছ', 'জ', 'ঝ', 'ঞ', 'ট', 'ঠ', 'ড', 'ঢ', 'ণ', 'ত', 'থ', 'দ',
-    'ধ', 'ন', 'প', 'ফ', 'ব', 'ভ', 'ম', 'য', 'র', 'ল', 'শ', 'ষ', 'স', 'হ', 'ৎ', 'ড়', 'ঢ়', 'য়', 'ং',
-    'ঃ', 'ঁ', '্',
-];
+pub mod bijoy;
+mod sanitize;
+
+pub use sanitize::sanitize;
+
+pub use crate::complete::{CompletionOptions, Order};
 
 pub fn suggest(word: &str) -> Vec<String> {
+    suggest_with_budget(word, &Budget::default()).words
+}
+
+/// Like [`suggest`], but stops completing `word` when `budget` runs out and
+/// returns the completions reached by then, flagged as truncated.
+pub fn suggest_with_budget(word: &str, budget: &Budget) -> Suggestions {
     if word.is_empty() {
-        return Vec::new();
+        return Suggestions::default();
     }
-    
-    let words = Lazy::force(&WORDS);
 
-    let need_chars_upto = match word.chars().count() {
-        1 => 0,
-        2..=3 => 1,
-        _ => 5,
+    // The same completions as suggest_with, as long as the word is in
+    // grapheme clusters
+    let options = CompletionOptions {
+        budget: budget.clone(),
+        ..Default::default()
     };
+    suggest_with(word, &options)
+}
 
-    let node = if let Some(n) = words.matching_node(word) {
-        n
+/// Completes the partially typed Bangla `word` with the dictionary words
+/// allowed by `options`.
+pub fn suggest_with(word: &str, options: &CompletionOptions) -> Suggestions {
+    if word.is_empty() {
+        return Suggestions::default();
+    }
+
+    let word = if options.sanitize {
+        Cow::Owned(sanitize(word))
     } else {
-        return Vec::new();
+        Cow::Borrowed(word)
     };
 
-    let mut nodes: Vec<_> = CHARS
-        .iter()
-        .filter_map(|&c| node.get_matching_node_by_char(c))
-        .collect();
+    let words = Lazy::force(&WORDS);
+    let mut arena = words.arena(&options.budget);
 
-    for _ in 0..(need_chars_upto - 1) {
-        let new_nodes: Vec<_> = nodes
-            .iter()
-            .flat_map(|node| {
-                CHARS
-                    .iter()
-                    .filter_map(|&c| node.get_matching_node_by_char(c))
-            })
-            .collect();
-
-        nodes.extend(new_nodes);
+    let Some(node) = arena.matching_node(&word) else {
+        return Suggestions {
+            words: Vec::new(),
+            truncated: arena.exhausted(),
+        };
+    };
+
+    let mut nodes = Vec::new();
+
+    if options.include_exact_match {
+        nodes.push(node);
     }
 
-    let suggestions = nodes
-        .into_iter()
-        .filter_map(|n| n.get_word())
-        .collect::<HashSet<_>>()
-        .into_iter()
-        .collect::<Vec<_>>();
+    let depth = options.depth_for(segment::count_clusters(&word));
+    complete::complete(&mut arena, node, word.chars().last(), depth, &mut nodes);
 
-    suggestions
+    Suggestions {
+        words: complete::collect_words(&arena, &nodes, options.order, options.max_results),
+        truncated: arena.exhausted(),
+    }
 }
 
 #[cfg(test)]
@@ -70,24 +81,165 @@ mod tests {
     #[test]
     fn test_suggestions() {
         assert_eq!(sort(sort(suggest(""))), Vec::<String>::new());
-        assert_eq!(sort(sort(suggest("আমা"))), ["আমান", "আমার", "আমায়"]);
+        // The completions add grapheme clusters, like the দের of আমাদের
+        let suggestions = suggest("আমা");
+        for word in ["আমার", "আমাদের", "আমা\u{09DF}"] {
+            assert!(suggestions.contains(&word.to_owned()), "{word}");
+        }
+        // A single letter is completed with a single cluster
+        let suggestions = suggest("ই");
+        assert!(suggestions.contains(&"ইচ্ছা".to_owned()));
+        assert!(suggestions.contains(&"ইন".to_owned()));
+        assert!(suggestions
+            .iter()
+            .all(|word| segment::count_clusters(word) == 2));
+        let suggestions = suggest("কম্পি");
+        for word in [
+            "কম্পিউটার",
+            "কম্পিউটিং",
+            "কম্পিউটেশন",
+            "কম্পিটিশন",
+            "কম্পিত",
+            "কম্পিতা",
+        ] {
+            assert!(suggestions.contains(&word.to_owned()), "{word}");
+        }
+        assert_eq!(sort(sort(suggest("আইনস্"))), ["আইনস্টাইন"]);
+        assert_eq!(
+            sort(sort(suggest("ঋক্"))),
+            ["ঋক্ষ", "ঋক্ষমণ্ডল", "ঋক্ষরাজ", "ঋক্\u{200C}", "ঋক্\u{200C}বেদ"]
+        );
+        assert_eq!(sort(sort(suggest("খ(১"))), Vec::<String>::new());
+        assert_eq!(sort(sort(suggest("1"))), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_suggestions_with_options() {
+        let options = CompletionOptions {
+            depth: Some(1),
+            order: Order::Alphabetical,
+            ..Default::default()
+        };
         assert_eq!(
-            sort(sort(suggest("ই"))),
-            ["ইজ", "ইট", "ইন", "ইফ", "ইভ", "ইহ"]
+            suggest_with("আমা", &options).words,
+            ["আমাকে", "আমাগো", "আমান", "আমার", "আমাশা", "আমা\u{09DF}"]
         );
+        assert!(suggest_with("", &options).words.is_empty());
+        assert!(suggest_with("1", &options).words.is_empty());
+
+        let options = CompletionOptions {
+            depth: Some(1),
+            include_exact_match: true,
+            order: Order::Alphabetical,
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest_with("আমা", &options).words,
+            ["আমা", "আমাকে", "আমাগো", "আমান", "আমার", "আমাশা", "আমা\u{09DF}"]
+        );
+
+        // A conjunct is a single grapheme cluster
+        let options = CompletionOptions {
+            depth: Some(3),
+            order: Order::ShortestFirst,
+            ..Default::default()
+        };
         assert_eq!(
-            sort(sort(suggest("কম্পি"))),
+            suggest_with("কম্পি", &options).words,
             [
+                "কম্পিত",
+                "কম্পিতা",
                 "কম্পিউটার",
                 "কম্পিউটিং",
-                "কম্পিউটেশন",
                 "কম্পিটিশন",
-                "কম্পিত",
-                "কম্পিতা"
+                "কম্পিউটার্স"
             ]
         );
-        assert_eq!(sort(sort(suggest("আইনস্"))), ["আইনস্টাইন"]);
-        assert_eq!(sort(sort(suggest("খ(১"))), Vec::<String>::new());
-        assert_eq!(sort(sort(suggest("1"))), Vec::<String>::new());
+
+        // Every character of the dictionary is completed, like the au length
+        // mark and ZWNJ
+        let options = CompletionOptions {
+            depth: Some(1),
+            ..Default::default()
+        };
+        assert!(suggest_with("চে", &options)
+            .words
+            .contains(&"চে\u{09D7}দ্দ".to_string()));
+        let options = CompletionOptions {
+            depth: Some(2),
+            order: Order::Alphabetical,
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest_with("ঋক্", &options).words,
+            ["ঋক্ষ", "ঋক্ষরাজ", "ঋক্\u{200C}", "ঋক্\u{200C}বেদ"]
+        );
+
+        let options = CompletionOptions {
+            depth: Some(0),
+            include_exact_match: true,
+            ..Default::default()
+        };
+        assert_eq!(suggest_with("আমা", &options).words, ["আমা"]);
+
+        let options = CompletionOptions {
+            depth: Some(5),
+            max_results: Some(3),
+            order: Order::ShortestFirst,
+            ..Default::default()
+        };
+        assert_eq!(
+            suggest_with("কম্পি", &options).words,
+            ["কম্পিত", "কম্পিতা", "কম্পিউটার"]
+        );
+
+        let options = CompletionOptions {
+            max_results: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(suggest_with("কম্পি", &options).words.len(), 2);
+
+        // Words typed in visual order are found once sanitized
+        let mut options = CompletionOptions {
+            depth: Some(0),
+            include_exact_match: true,
+            ..Default::default()
+        };
+        assert!(suggest_with("েদশ", &options).words.is_empty());
+        options.sanitize = true;
+        assert_eq!(suggest_with("েদশ", &options).words[0], "দেশ");
+        assert_eq!(suggest_with("পাড\u{09BC}া", &options).words[0], "পা\u{09DC}া");
+    }
+
+    #[test]
+    fn test_suggestions_with_budget() {
+        let result = suggest_with_budget("কম্পি", &Budget::default());
+        assert!(!result.truncated);
+        assert_eq!(sort(result.words), sort(suggest("কম্পি")));
+
+        let budget = Budget {
+            max_nodes: Some(20),
+            ..Default::default()
+        };
+        let result = suggest_with_budget("কম্পি", &budget);
+        assert!(result.truncated);
+        assert!(result.words.len() < suggest("কম্পি").len());
+
+        // Even the typed word can't be reached
+        let budget = Budget {
+            max_nodes: Some(2),
+            ..Default::default()
+        };
+        let result = suggest_with_budget("কম্পি", &budget);
+        assert!(result.truncated);
+        assert!(result.words.is_empty());
+
+        let token = crate::CancelToken::new();
+        token.cancel();
+        let budget = Budget {
+            cancel: Some(token),
+            ..Default::default()
+        };
+        assert!(suggest_with_budget("আমা", &budget).truncated);
     }
 }
@@ -0,0 +1,131 @@
+use crate::{layouts::reorder_pre_base_kars, segment::is_consonant};
+
+const HASANTA: char = '\u{09CD}';
+const NUKTA: char = '\u{09BC}';
+const E_KAR: char = '\u{09C7}';
+
+/// Fixes the Bangla `word` typed in visual order or with slips into the
+/// logical Unicode order of the dictionary words:
+///
+/// - A pre-base kar (ি, ে or ৈ) typed before its consonant or conjunct is
+///   moved after it. A kar after a consonant is taken to be in its place.
+/// - An e-kar with an aa-kar or the au length mark makes the o-kar or the
+///   au-kar, around the consonant or after it.
+/// - Doubled hasantas are typed once.
+/// - A nukta after ড, ঢ or য makes ড়, ঢ় or য়, and a nukta after no
+///   consonant is dropped.
+pub fn sanitize(word: &str) -> String {
+    let mut cleaned = String::with_capacity(word.len());
+
+    for c in word.chars() {
+        let last = cleaned.chars().last();
+
+        match (last, c) {
+            (Some(HASANTA), HASANTA) => {}
+            (Some(base), NUKTA) => match nukta_form(base) {
+                Some(composed) => {
+                    cleaned.pop();
+                    cleaned.push(composed);
+                }
+                None if is_consonant(base) && !has_nukta(base) => cleaned.push(NUKTA),
+                None => {}
+            },
+            (None, NUKTA) => {}
+            (Some(E_KAR), '\u{09BE}' | '\u{09D7}') if follows_consonant(&cleaned) => {
+                cleaned.pop();
+                cleaned.push(if c == '\u{09BE}' {
+                    '\u{09CB}'
+                } else {
+                    '\u{09CC}'
+                });
+            }
+            _ => cleaned.push(c),
+        }
+    }
+
+    reorder_pre_base_kars(&cleaned, true)
+}
+
+/// The consonant `c` with a nukta, when it is a letter of its own.
+pub(super) fn nukta_form(c: char) -> Option<char> {
+    match c {
+        'ড' => Some('\u{09DC}'),
+        'ঢ' => Some('\u{09DD}'),
+        'য' => Some('\u{09DF}'),
+        _ => None,
+    }
+}
+
+fn has_nukta(c: char) -> bool {
+    matches!(c, '\u{09DC}' | '\u{09DD}' | '\u{09DF}' | NUKTA)
+}
+
+/// Returns whether the e-kar ending `text` follows a consonant.
+fn follows_consonant(text: &str) -> bool {
+    let mut chars = text.chars().rev().skip(1);
+    chars.next().is_some_and(|c| is_consonant(c) || c == NUKTA)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        let cases = [
+            // Valid words are kept
+            ("", ""),
+            ("কি", "কি"),
+            ("কেক", "কেক"),
+            ("বাংলা", "বাংলা"),
+            ("কোন", "কোন"),
+            ("পা\u{09DC}া", "পা\u{09DC}া"),
+            ("ক্\u{200C}ষ", "ক্\u{200C}ষ"),
+            // Pre-base kars before their consonant
+            ("িক", "কি"),
+            ("েক", "কে"),
+            ("ৈক", "কৈ"),
+            ("িদন", "দিন"),
+            ("আিম", "আমি"),
+            ("কিেক", "কিকে"),
+            // ...or their conjunct
+            ("িস্ত্র", "স্ত্রি"),
+            ("েক্ষত্র", "ক্ষেত্র"),
+            ("েপ্রম", "প্রেম"),
+            ("িড\u{09BC}", "\u{09DC}ি"),
+            ("েদ\u{09BC}", "দ\u{09BC}ে"),
+            // With no consonant after them they stay
+            ("কাি", "কাি"),
+            ("ি", "ি"),
+            ("িি", "িি"),
+            // The o-kar and au-kar
+            ("কো", "কো"),
+            ("কৌ", "কৌ"),
+            ("েকা", "কো"),
+            ("েকৗ", "কৌ"),
+            ("েশাভা", "শোভা"),
+            ("অা", "অা"),
+            // Doubled hasantas
+            ("ক্্ষ", "ক্ষ"),
+            ("ক্্্ষ", "ক্ষ"),
+            ("ক্", "ক্"),
+            // Nuktas
+            ("ড\u{09BC}", "\u{09DC}"),
+            ("ঢ\u{09BC}", "\u{09DD}"),
+            ("য\u{09BC}", "\u{09DF}"),
+            ("পাড\u{09BC}া", "পা\u{09DC}া"),
+            ("\u{09DC}\u{09BC}", "\u{09DC}"),
+            ("ক\u{09BC}", "ক\u{09BC}"),
+            ("ক\u{09BC}\u{09BC}", "ক\u{09BC}"),
+            ("\u{09BC}ক", "ক"),
+            ("আ\u{09BC}", "আ"),
+            ("কা\u{09BC}", "কা"),
+            // Everything at once
+            ("েড\u{09BC}া্্", "\u{09DC}ো্"),
+        ];
+
+        for (typed, sanitized) in cases {
+            assert_eq!(sanitize(typed), sanitized, "{typed:?}");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+/// How often the deadline and the cancellation token are checked, in visited
+/// nodes, as reading the clock for every node would be too costly.
+const CHECK_INTERVAL: usize = 64;
+
+/// Limits on the work done by a single suggestion query.
+///
+/// When a limit is reached the query stops walking the dictionary and returns
+/// the suggestions found so far, flagged as [`Suggestions::truncated`]. The
+/// default budget is unlimited.
+#[derive(Clone, Debug, Default)]
+pub struct Budget {
+    /// The maximum number of dictionary nodes visited.
+    pub max_nodes: Option<usize>,
+    /// The time by which the query must finish.
+    pub deadline: Option<Instant>,
+    /// A token for cancelling the query from another thread.
+    pub cancel: Option<CancelToken>,
+}
+
+impl Budget {
+    /// Returns whether a query which has visited `visited` nodes may visit
+    /// one more.
+    pub(crate) fn allows(&self, visited: usize) -> bool {
+        if self.max_nodes.is_some_and(|max| visited >= max) {
+            return false;
+        }
+
+        if visited % CHECK_INTERVAL == 0 {
+            if self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                return false;
+            }
+
+            if self.cancel.as_ref().is_some_and(CancelToken::is_cancelled) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// A token for cooperatively cancelling a running query.
+///
+/// Clones of a token share its state, so one clone can be given to the query
+/// in its [`Budget`] while another is cancelled, for example when the user
+/// types the next key.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The suggestions of a query run with a [`Budget`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Suggestions {
+    pub words: Vec<String>,
+    /// Whether the query ran out of its budget, so that some suggestions may
+    /// be missing.
+    pub truncated: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_budget() {
+        assert!(Budget::default().allows(usize::MAX));
+
+        let budget = Budget {
+            max_nodes: Some(10),
+            ..Default::default()
+        };
+        assert!(budget.allows(9));
+        assert!(!budget.allows(10));
+
+        let budget = Budget {
+            deadline: Some(Instant::now() - Duration::from_millis(1)),
+            ..Default::default()
+        };
+        assert!(!budget.allows(0));
+
+        let token = CancelToken::new();
+        let budget = Budget {
+            cancel: Some(token.clone()),
+            ..Default::default()
+        };
+        assert!(budget.allows(0));
+        token.cancel();
+        assert!(!budget.allows(0));
+    }
+}
@@ -0,0 +1,150 @@
+use crate::{
+    fst::{FstArena, NodeId},
+    segment::extends_cluster,
+    Budget,
+};
+
+/// Options for completing a partially typed word.
+#[derive(Clone, Debug, Default)]
+pub struct CompletionOptions {
+    /// The maximum number of grapheme clusters (like `ক্ষ` or `মা`) added to
+    /// the typed word. By default it depends on the length of the typed word.
+    pub depth: Option<usize>,
+    /// The maximum number of completions returned.
+    pub max_results: Option<usize>,
+    /// Whether the typed word itself is returned when it is a dictionary word.
+    pub include_exact_match: bool,
+    /// The order of the completions.
+    pub order: Order,
+    /// Whether a typed Bangla word is fixed with [`bangla::sanitize`] before
+    /// it is completed, for the words typed in visual order.
+    ///
+    /// [`bangla::sanitize`]: crate::bangla::sanitize
+    pub sanitize: bool,
+    /// Limits on the work done by the query.
+    pub budget: Budget,
+}
+
+impl CompletionOptions {
+    /// The number of grapheme clusters to add to a typed word of `clusters`
+    /// grapheme clusters, if not set in the options: a single letter is
+    /// completed with one more, so that it doesn't give a large part of the
+    /// dictionary, and longer words with up to five.
+    pub(crate) fn depth_for(&self, clusters: usize) -> usize {
+        self.depth.unwrap_or(match clusters {
+            0..=1 => 1,
+            _ => 5,
+        })
+    }
+}
+
+/// The order of the completions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Order {
+    /// In the order they are found, which is the cheapest.
+    #[default]
+    Unordered,
+    Alphabetical,
+    /// The shortest completions first, alphabetically among the same length.
+    ShortestFirst,
+}
+
+/// Finds the nodes reached from `from` by adding at most `depth` grapheme
+/// clusters, where `last` is the last character walked to reach `from`.
+pub(crate) fn complete<D: AsRef<[u8]>>(
+    arena: &mut FstArena<'_, D>,
+    from: NodeId,
+    last: Option<char>,
+    depth: usize,
+    nodes: &mut Vec<NodeId>,
+) {
+    for transition in arena.transitions(from) {
+        let c = transition.input;
+        let starts_cluster = !extends_cluster(last, c);
+
+        if starts_cluster && depth == 0 {
+            continue;
+        }
+
+        let Some(next) = arena.follow(from, transition) else {
+            return;
+        };
+
+        nodes.push(next);
+        complete(
+            arena,
+            next,
+            Some(c),
+            depth - usize::from(starts_cluster),
+            nodes,
+        );
+    }
+}
+
+/// Makes the words of the final nodes, in `order` and at most `max_results`
+/// of them.
+pub(crate) fn collect_words<D: AsRef<[u8]>>(
+    arena: &FstArena<'_, D>,
+    nodes: &[NodeId],
+    order: Order,
+    max_results: Option<usize>,
+) -> Vec<String> {
+    let words = nodes.iter().filter_map(|&n| arena.get_word(n));
+    order_words(words, order, max_results)
+}
+
+/// Puts the `words` in `order`, keeping at most `max_results` of them.
+pub(crate) fn order_words(
+    words: impl Iterator<Item = String>,
+    order: Order,
+    max_results: Option<usize>,
+) -> Vec<String> {
+    let mut words = match (order, max_results) {
+        (Order::Unordered, Some(max)) => return words.take(max).collect(),
+        _ => words.collect::<Vec<_>>(),
+    };
+
+    match order {
+        Order::Unordered => (),
+        Order::Alphabetical => words.sort_unstable(),
+        Order::ShortestFirst => {
+            words.sort_unstable_by(|a, b| a.chars().count().cmp(&b.chars().count()).then(a.cmp(b)))
+        }
+    }
+
+    if let Some(max) = max_results {
+        words.truncate(max);
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{avro::Suggest, bangla};
+
+    #[test]
+    fn test_default_depth() {
+        let options = CompletionOptions::default();
+        let completes = |word: &str, completion: &str| {
+            bangla::suggest_with(word, &options)
+                .words
+                .contains(&completion.to_owned())
+        };
+
+        assert!(completes("বাংলা", "বাংলাদেশ"));
+        assert!(completes("কম্পি", "কম্পিউটার"));
+        assert!(completes("স্বাধী", "স্বাধীনতা"));
+        assert!(completes("বিশ্ব", "বিশ্ববিদ্যাল\u{09DF}"));
+        // A single letter is completed with a single one
+        assert!(completes("ক", "কথা"));
+        assert!(!completes("ক", "কম্পিউটার"));
+
+        let suggest = Suggest::new();
+        let words = suggest.suggest_completions("bangla", &options).words;
+        assert!(words.contains(&"বাংলাদেশ".to_owned()));
+        let words = suggest.suggest_completions("kompi", &options).words;
+        assert!(words.contains(&"কম্পিউটার".to_owned()));
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    avro::PatternSet,
+    segment::{grapheme_clusters, is_consonant},
+};
+
+const HASANTA: char = '\u{09CD}';
+const NUKTA: char = '\u{09BC}';
+
+/// The letters with a nukta and the o-kar and au-kar, with the characters
+/// they are made of.
+const COMPOSED: [(char, [char; 2]); 5] = [
+    ('\u{09DC}', ['ড', NUKTA]),
+    ('\u{09DD}', ['ঢ', NUKTA]),
+    ('\u{09DF}', ['য', NUKTA]),
+    ('\u{09CB}', ['\u{09C7}', '\u{09BE}']),
+    ('\u{09CC}', ['\u{09C7}', '\u{09D7}']),
+];
+
+/// The Avro patterns, ranked, of every transliteration.
+static AVRO_KEYS: Lazy<HashMap<String, Vec<String>>> = Lazy::new(|| {
+    let mut reverse = PatternSet::avro().reverse();
+
+    let mut alternatives: HashMap<String, usize> = HashMap::new();
+    for (pattern, _) in reverse.values().flatten() {
+        *alternatives.entry(pattern.clone()).or_default() += 1;
+    }
+
+    // The pattern giving the transliteration with the highest weight first,
+    // then the one with the fewest alternatives and the shortest one
+    reverse
+        .drain()
+        .map(|(transliteration, mut patterns)| {
+            patterns.sort_by(|(a, a_weight), (b, b_weight)| {
+                b_weight
+                    .cmp(a_weight)
+                    .then(alternatives[a].cmp(&alternatives[b]))
+                    .then(a.len().cmp(&b.len()))
+                    .then(a.cmp(b))
+            });
+            let patterns = patterns.into_iter().map(|(pattern, _)| pattern).collect();
+            (transliteration, patterns)
+        })
+        .collect()
+});
+
+/// Splits the Bengali `cluster`, like a conjunct (যুক্তবর্ণ), into the
+/// characters it is built from: `ক্ষ` is `ক`, `্` and `ষ`.
+///
+/// The letters with a nukta, like ড়, are split into the letter and the
+/// nukta, and the o-kar and au-kar into the e-kar and their second part.
+pub fn decompose(cluster: &str) -> Vec<char> {
+    let mut parts = Vec::new();
+
+    for c in cluster.chars() {
+        match COMPOSED.iter().find(|&&(composed, _)| composed == c) {
+            Some((_, decomposed)) => parts.extend(decomposed),
+            None => parts.push(c),
+        }
+    }
+
+    parts
+}
+
+/// Builds the text of the `parts` of a cluster, the reverse of
+/// [`decompose`].
+pub fn compose(parts: &[char]) -> String {
+    let mut composed = String::new();
+
+    for &c in parts {
+        let last = composed.chars().last();
+        let pair = COMPOSED
+            .iter()
+            .find(|(_, decomposed)| Some(decomposed[0]) == last && decomposed[1] == c);
+
+        match pair {
+            Some(&(pair, _)) => {
+                composed.pop();
+                composed.push(pair);
+            }
+            None => composed.push(c),
+        }
+    }
+
+    composed
+}
+
+/// Returns the letters of the `conjunct` joined by hasantas, like `ক` and `ষ`
+/// of `ক্ষ`, or `None` if it is not a conjunct of consonants.
+pub fn letters(conjunct: &str) -> Option<Vec<&str>> {
+    let mut letters = Vec::new();
+
+    for letter in conjunct.split(HASANTA) {
+        let mut chars = letter.chars();
+        let first = chars.next()?;
+        if !is_consonant(first) || !chars.all(|c| c == NUKTA) {
+            return None;
+        }
+        letters.push(letter);
+    }
+
+    (letters.len() > 1).then_some(letters)
+}
+
+/// Returns the Roman keys typing the `conjunct` in Avro Phonetic, like `ks`
+/// for `ক্ষ` or `st` for `স্ত`.
+///
+/// The keys of a pattern giving the whole conjunct are preferred, ranked by
+/// the weight of the conjunct for the pattern, but not the keys of a single
+/// letter, like `g` for জ্ঞ, which types গ. Otherwise the conjunct is typed
+/// in parts, the fewest and longest first, as long as their keys are not read
+/// back as other patterns. Returns `None` if the conjunct can't be typed.
+pub fn avro_keys(conjunct: &str) -> Option<String> {
+    if grapheme_clusters(conjunct).count() != 1 {
+        return None;
+    }
+    let letters = letters(conjunct);
+    if let Some(pattern) = pattern_typing(conjunct, letters.is_some()) {
+        return Some(pattern.clone());
+    }
+
+    let letters = letters?;
+    let patterns = PatternSet::avro();
+
+    let mut candidates = Vec::new();
+    split_keys(&letters, &mut Vec::new(), &mut candidates);
+    candidates.sort_by_key(|keys| keys.len());
+
+    candidates
+        .into_iter()
+        .find(|keys| reads_back(&patterns, keys))
+        .map(|keys| keys.concat())
+}
+
+/// Returns the best pattern giving the whole `text`. The pattern of a
+/// `conjunct` is not the key of a single letter, like `g` of গ for জ্ঞ,
+/// which is read back as that letter.
+fn pattern_typing(text: &str, conjunct: bool) -> Option<&'static String> {
+    AVRO_KEYS
+        .get(text)?
+        .iter()
+        .find(|pattern| !conjunct || !is_letter_key(pattern))
+}
+
+/// Returns whether `pattern` is the key of a consonant.
+fn is_letter_key(pattern: &str) -> bool {
+    AVRO_KEYS.iter().any(|(transliteration, patterns)| {
+        let mut chars = transliteration.chars();
+        chars.next().is_some_and(is_consonant)
+            && chars.next().is_none()
+            && patterns.first().is_some_and(|first| first == pattern)
+    })
+}
+
+/// Adds to `candidates` every way of typing the `letters` in parts which
+/// have keys, after the keys typed `so_far`, with the longer parts first.
+fn split_keys<'k>(letters: &[&str], so_far: &mut Vec<&'k str>, candidates: &mut Vec<Vec<&'k str>>) {
+    if letters.is_empty() {
+        candidates.push(so_far.clone());
+        return;
+    }
+
+    for length in (1..=letters.len()).rev() {
+        let part = letters[..length].join("\u{09CD}");
+        let Some(key) = pattern_typing(&part, length > 1) else {
+            continue;
+        };
+
+        so_far.push(key);
+        split_keys(&letters[length..], so_far, candidates);
+        so_far.pop();
+    }
+}
+
+/// Returns whether the `keys` typed one after the other are read back as
+/// the same patterns.
+fn reads_back(patterns: &PatternSet, keys: &[&str]) -> bool {
+    let typed = keys.concat();
+    let mut remaining = typed.as_str();
+
+    keys.iter().all(|&key| match patterns.segment(remaining) {
+        Some((pattern, rest)) if pattern == key => {
+            remaining = rest;
+            true
+        }
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompose() {
+        assert_eq!(decompose("ক্ষ"), ['ক', HASANTA, 'ষ']);
+        assert_eq!(decompose("ক্ষ্ম"), ['ক', HASANTA, 'ষ', HASANTA, 'ম']);
+        assert_eq!(decompose("র্কো"), ['র', HASANTA, 'ক', 'ে', 'া']);
+        assert_eq!(decompose("\u{09DC}্গ"), ['ড', NUKTA, HASANTA, 'গ']);
+        assert!(decompose("").is_empty());
+
+        for cluster in ["ক্ষ", "ন্ত্র", "র্কো", "\u{09DC}্গ", "ক্\u{200C}", "গৌ", "আ"]
+        {
+            assert_eq!(compose(&decompose(cluster)), cluster);
+        }
+        assert_eq!(compose(&['ক', HASANTA, 'ত']), "ক্ত");
+        assert_eq!(compose(&['য', NUKTA, 'ে', 'া']), "\u{09DF}ো");
+    }
+
+    #[test]
+    fn test_letters() {
+        assert_eq!(letters("ক্ষ"), Some(vec!["ক", "ষ"]));
+        assert_eq!(letters("ন্ত্র"), Some(vec!["ন", "ত", "র"]));
+        assert_eq!(letters("ড\u{09BC}্গ"), Some(vec!["ড\u{09BC}", "গ"]));
+        assert_eq!(letters("ক"), None);
+        assert_eq!(letters("ক্"), None);
+        assert_eq!(letters("কা"), None);
+        assert_eq!(letters("অ্য"), None);
+    }
+
+    #[test]
+    fn test_avro_keys() {
+        assert_eq!(avro_keys("ক্ষ").as_deref(), Some("ks"));
+        assert_eq!(avro_keys("ক্ক").as_deref(), Some("kk"));
+        assert_eq!(avro_keys("ন্ত").as_deref(), Some("nt"));
+        assert_eq!(avro_keys("ম্প").as_deref(), Some("mp"));
+        // Not `g`, typing গ
+        assert_eq!(avro_keys("জ্ঞ").as_deref(), Some("gg"));
+        assert_eq!(avro_keys("ক্ষ্ম").as_deref(), Some("kxm"));
+        // Typed in parts
+        assert_eq!(avro_keys("স্ত").as_deref(), Some("st"));
+        assert_eq!(avro_keys("প্ত").as_deref(), Some("pt"));
+        assert_eq!(avro_keys("ন্ত্র").as_deref(), Some("ntr"));
+        assert_eq!(avro_keys("ক"), Some("k".to_owned()));
+        assert_eq!(avro_keys("ক্ষা"), None);
+        assert_eq!(avro_keys("কক"), None);
+    }
+}
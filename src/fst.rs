@@ -1,4 +1,14 @@
-use fst::raw::{Fst, Node};
+use std::{
+    collections::HashMap,
+    hash::{BuildHasherDefault, Hasher},
+};
+
+use fst::{
+    raw::{CompiledAddr, Fst, Node, Output},
+    Automaton, Streamer,
+};
+
+use crate::Budget;
 
 #[derive(Clone)]
 pub struct FstTree<D: AsRef<[u8]>> {
@@ -12,11 +22,10 @@ impl<D: AsRef<[u8]>> FstTree<D> {
     }
 
     pub fn match_longest_common_prefix<'a>(&self, prefix: &'a str) -> (&'a str, &'a str, bool) {
-        let mut iter = prefix.chars();
         let mut index = 0;
         let mut node = self.fst.root();
 
-        while let Some(c) = iter.next() {
+        for c in prefix.chars() {
             match node.find_input(c as u8) {
                 Some(addr) => {
                     node = self.fst.node(node.transition_addr(addr));
@@ -31,37 +40,141 @@ impl<D: AsRef<[u8]>> FstTree<D> {
         (matched_prefix, remaining, node.is_final())
     }
 
-    pub fn matching_node<'a>(&'a self, word: &str) -> Option<FstNode<'a, D>> {
-        let mut iter = word.chars();
+    /// Returns the value associated with `key` when the FST is used as a map.
+    pub fn get(&self, key: &str) -> Option<u64> {
         let mut node = self.fst.root();
+        let mut output = Output::zero();
 
-        while let Some(c) = iter.next() {
-            match node.find_input(c as u8) {
-                Some(addr) => {
-                    node = self.fst.node(node.transition_addr(addr));
-                }
-                None => return None,
-            }
+        for c in key.chars() {
+            let transition = node.transition(node.find_input(c as u8)?);
+            output = output.cat(transition.out);
+            node = self.fst.node(transition.addr);
+        }
+
+        node.is_final()
+            .then(|| output.cat(node.final_output()).value())
+    }
+
+    /// Returns the keys of the FST with their values, decoding the bytes of
+    /// the keys after the first `skip` ones.
+    pub fn entries(&self, skip: usize) -> Vec<(Vec<u8>, String, u64)> {
+        let mut entries = Vec::new();
+        let mut stream = self.fst.stream();
+
+        while let Some((key, output)) = stream.next() {
+            let (prefix, rest) = key.split_at(skip.min(key.len()));
+            let decoded = rest.iter().map(|&byte| decode(byte)).collect();
+            entries.push((prefix.to_vec(), decoded, output.value()));
+        }
+
+        entries
+    }
+
+    /// Creates an empty arena for walking the FST, which stops walking when
+    /// `budget` runs out.
+    pub fn arena(&self, budget: &Budget) -> FstArena<'_, D> {
+        FstArena::new(&self.fst, budget.clone())
+    }
+
+    /// Returns the automaton matching the suffixes of the keys starting with
+    /// `prefix`.
+    pub fn automaton(&self, prefix: &[u8]) -> Option<FstAutomaton<'_, D>> {
+        let mut node = self.fst.root();
+        let mut output = Output::zero();
+
+        for &byte in prefix {
+            let transition = node.transition(node.find_input(byte)?);
+            output = output.cat(transition.out);
+            node = self.fst.node(transition.addr);
         }
 
-        Some(FstNode {
+        Some(FstAutomaton {
             fst: &self.fst,
-            node,
-            word: word.to_string(),
+            start: node,
+            output,
         })
     }
 }
 
+/// An [`Automaton`] which can list the bytes it may accept from a state.
+///
+/// Intersecting it with the FST follows only those bytes instead of trying
+/// every transition of the FST nodes, which have many transitions near the
+/// root.
+pub trait SparseAutomaton: Automaton {
+    fn inputs<'s>(&'s self, state: &'s Self::State) -> impl Iterator<Item = u8> + 's;
+
+    /// The weight of the match at `state`, which ranks it against the other
+    /// matches of the automaton.
+    fn weight(&self, _state: &Self::State) -> u64 {
+        0
+    }
+}
+
+/// An automaton matching the keys of an FST, or the part of the keys after a
+/// prefix. The weight of a match is the value of its key in the FST.
+pub struct FstAutomaton<'a, D: AsRef<[u8]>> {
+    fst: &'a Fst<D>,
+    start: Node<'a>,
+    /// The output of the prefix.
+    output: Output,
+}
+
+impl<'a, D: AsRef<[u8]>> Automaton for FstAutomaton<'a, D> {
+    type State = Option<(Node<'a>, Output)>;
+
+    fn start(&self) -> Self::State {
+        Some((self.start, self.output))
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        state.is_some_and(|(node, _)| node.is_final())
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        state.is_some()
+    }
+
+    fn accept(&self, state: &Self::State, byte: u8) -> Self::State {
+        let (node, output) = (*state)?;
+        let transition = node.transition(node.find_input(byte)?);
+
+        Some((self.fst.node(transition.addr), output.cat(transition.out)))
+    }
+}
+
+impl<D: AsRef<[u8]>> SparseAutomaton for FstAutomaton<'_, D> {
+    fn inputs<'s>(&'s self, state: &'s Self::State) -> impl Iterator<Item = u8> + 's {
+        state
+            .iter()
+            .flat_map(|(node, _)| node.transitions().map(|transition| transition.inp))
+    }
+
+    fn weight(&self, state: &Self::State) -> u64 {
+        state.map_or(0, |(node, output)| output.cat(node.final_output()).value())
+    }
+}
+
+#[cfg(test)]
+impl<D: AsRef<[u8]>> FstTree<D> {
+    pub fn as_bytes(&self) -> &[u8] {
+        self.fst.as_bytes()
+    }
+}
+
 #[cfg(test)]
 impl FstTree<Vec<u8>> {
-    fn from_strings(mut set: Vec<&str>) -> Self {
+    fn from_strings(set: Vec<&str>) -> Self {
+        // Convert each Bengali character to its single byte representation
+        let mut set = set
+            .iter()
+            .map(|word| word.chars().map(|c| c as u8).collect::<Vec<u8>>())
+            .collect::<Vec<_>>();
         set.sort();
 
         let mut builder = fst::raw::Builder::memory();
 
-        for word in set {
-            // Convert each Bengali character to its single byte representation
-            let numbered_word = word.chars().map(|c| c as u8).collect::<Vec<u8>>();
+        for numbered_word in set {
             builder.add(&numbered_word).unwrap();
         }
 
@@ -71,59 +184,301 @@ impl FstTree<Vec<u8>> {
     }
 }
 
-#[derive(Clone)]
-pub struct FstNode<'a, D: AsRef<[u8]>> {
+/// A transition going out of a node of the FST.
+#[derive(Clone, Copy, Debug)]
+pub struct Transition {
+    /// The character of the transition.
+    pub input: char,
+    addr: CompiledAddr,
+}
+
+/// A node of the FST reached while walking it in a [`FstArena`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u32);
+
+/// The root node of the FST, which is always the first step of an arena.
+const ROOT: NodeId = NodeId(0);
+
+#[derive(Clone, Copy)]
+struct Step {
+    addr: CompiledAddr,
+    parent: NodeId,
+    input: char,
+}
+
+impl Step {
+    /// The key identifying the step among the steps of the arena.
+    fn key(parent: NodeId, input: char) -> u64 {
+        (u64::from(parent.0) << 32) | u64::from(input)
+    }
+}
+
+/// An arena of the paths walked in a [`FstTree`].
+///
+/// Every node reached is stored as its address and the node it was reached
+/// from, so walking the FST doesn't build a `String` at every step. The words
+/// are only made for the final nodes asked for with [`FstArena::get_word`].
+///
+/// A step is stored only once, so the same word always gets the same
+/// [`NodeId`] and duplicate nodes can be removed by comparing their ids.
+///
+/// Once the [`Budget`] of the arena runs out, no more nodes are reached and
+/// the walks fail.
+pub struct FstArena<'a, D: AsRef<[u8]>> {
     fst: &'a Fst<D>,
-    node: Node<'a>,
-    word: String,
+    steps: Vec<Step>,
+    interned: HashMap<u64, NodeId, BuildHasherDefault<StepHasher>>,
+    /// The transitions of the walk in progress, kept until the walk succeeds.
+    walk: Vec<(CompiledAddr, char)>,
+    /// The last node parsed, as the same node is usually probed many times.
+    last: Option<(NodeId, Node<'a>)>,
+    budget: Budget,
+    exhausted: bool,
 }
 
-impl<'a, D: AsRef<[u8]>> FstNode<'a, D> {
-    pub fn get_matching_node(&self, suffix: &str) -> Option<FstNode<'a, D>> {
-        let mut iter = suffix.chars();
-        let mut node = self.node;
+impl<'a, D: AsRef<[u8]>> FstArena<'a, D> {
+    fn new(fst: &'a Fst<D>, budget: Budget) -> Self {
+        let root = Step {
+            addr: fst.root().addr(),
+            parent: ROOT,
+            input: '\0',
+        };
+
+        FstArena {
+            fst,
+            steps: vec![root],
+            interned: HashMap::default(),
+            walk: Vec::new(),
+            last: None,
+            budget,
+            exhausted: false,
+        }
+    }
 
-        while let Some(c) = iter.next() {
-            match node.find_input(c as u8) {
-                Some(addr) => {
-                    node = self.fst.node(node.transition_addr(addr));
-                }
-                None => return None,
-            }
+    /// Returns whether the budget ran out, so that some nodes were not reached.
+    pub fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    pub fn root(&self) -> NodeId {
+        ROOT
+    }
+
+    pub fn matching_node(&mut self, word: &str) -> Option<NodeId> {
+        self.get_matching_node(ROOT, word)
+    }
+
+    pub fn get_matching_node(&mut self, from: NodeId, suffix: &str) -> Option<NodeId> {
+        let mut node = self.cached_node(from);
+        self.walk.clear();
+
+        for c in suffix.chars() {
+            let addr = node.transition_addr(node.find_input(c as u8)?);
+            node = self.fst.node(addr);
+            self.walk.push((addr, c));
+        }
+
+        let mut current = from;
+        for i in 0..self.walk.len() {
+            let (addr, c) = self.walk[i];
+            current = self.push(addr, current, c)?;
         }
 
-        let word = self.word.clone() + suffix;
+        Some(current)
+    }
+
+    /// Returns the transitions going out of `id`, which can be followed with
+    /// [`FstArena::follow`] without probing every possible character.
+    pub fn transitions(&self, id: NodeId) -> impl Iterator<Item = Transition> + 'a {
+        let node = self.node(id);
 
-        Some(FstNode {
-            fst: self.fst,
-            node,
-            word,
+        (0..node.len()).map(move |i| {
+            let transition = node.transition(i);
+
+            Transition {
+                input: decode(transition.inp),
+                addr: transition.addr,
+            }
         })
     }
 
-    pub fn get_matching_node_by_char(&self, suffix: char) -> Option<FstNode<'a, D>> {
-        let mut node = self.node;
+    /// Follows a transition returned by [`FstArena::transitions`] for `from`.
+    pub fn follow(&mut self, from: NodeId, transition: Transition) -> Option<NodeId> {
+        self.push(transition.addr, from, transition.input)
+    }
+
+    /// Walks every path from `from` that is accepted by `automaton`, adding
+    /// the nodes where the automaton matches to `matches` along with the
+    /// weight of the match.
+    ///
+    /// Paths sharing a prefix are walked only once, unlike walking each of the
+    /// strings the automaton matches one by one.
+    pub fn intersect<A: SparseAutomaton>(
+        &mut self,
+        from: NodeId,
+        automaton: &A,
+        matches: &mut Vec<(NodeId, u64)>,
+    ) {
+        let state = automaton.start();
+
+        if automaton.is_match(&state) {
+            matches.push((from, automaton.weight(&state)));
+        }
 
-        match node.find_input(suffix as u8) {
-            Some(addr) => {
-                node = self.fst.node(node.transition_addr(addr));
+        self.intersect_from(from, automaton, &state, matches);
+    }
+
+    fn intersect_from<A: SparseAutomaton>(
+        &mut self,
+        id: NodeId,
+        automaton: &A,
+        state: &A::State,
+        matches: &mut Vec<(NodeId, u64)>,
+    ) {
+        let node = self.node(id);
+
+        for input in automaton.inputs(state) {
+            let Some(i) = node.find_input(input) else {
+                continue;
+            };
+            let next = automaton.accept(state, input);
+
+            if !automaton.can_match(&next) {
+                continue;
+            }
+
+            let Some(child) = self.push(node.transition_addr(i), id, decode(input)) else {
+                return;
+            };
+
+            if automaton.is_match(&next) {
+                matches.push((child, automaton.weight(&next)));
             }
-            None => return None,
+
+            self.intersect_from(child, automaton, &next, matches);
         }
+    }
 
-        Some(FstNode {
-            fst: self.fst,
-            node,
-            word: format!("{}{}", self.word, suffix),
-        })
+    pub fn is_final(&self, id: NodeId) -> bool {
+        self.node(id).is_final()
+    }
+
+    /// Returns the word walked to reach `id` if it is a complete word.
+    pub fn get_word(&self, id: NodeId) -> Option<String> {
+        if !self.is_final(id) {
+            return None;
+        }
+
+        Some(self.path(id))
+    }
+
+    /// Returns the last character walked to reach `id`, or `None` for the
+    /// root.
+    pub fn last_char(&self, id: NodeId) -> Option<char> {
+        (id != ROOT).then(|| self.steps[id.0 as usize].input)
+    }
+
+    /// Returns the characters walked to reach `id`, whether it is a complete
+    /// word or not.
+    pub fn path(&self, id: NodeId) -> String {
+        let mut word = String::new();
+        self.write_word(id, &mut word);
+        word
+    }
+
+    fn write_word(&self, id: NodeId, word: &mut String) {
+        if id == ROOT {
+            return;
+        }
+
+        let step = self.steps[id.0 as usize];
+        self.write_word(step.parent, word);
+        word.push(step.input);
     }
 
-    pub fn get_word(self) -> Option<String> {
-        if self.node.is_final() {
-            Some(self.word)
-        } else {
-            None
+    fn push(&mut self, addr: CompiledAddr, parent: NodeId, input: char) -> Option<NodeId> {
+        if self.exhausted {
+            return None;
         }
+
+        let key = Step::key(parent, input);
+
+        if let Some(&id) = self.interned.get(&key) {
+            return Some(id);
+        }
+
+        // The root is not a visited node
+        if !self.budget.allows(self.steps.len() - 1) {
+            self.exhausted = true;
+            return None;
+        }
+
+        self.steps.push(Step {
+            addr,
+            parent,
+            input,
+        });
+
+        let id = NodeId(self.steps.len() as u32 - 1);
+        self.interned.insert(key, id);
+
+        Some(id)
+    }
+
+    fn node(&self, id: NodeId) -> Node<'a> {
+        self.fst.node(self.steps[id.0 as usize].addr)
+    }
+
+    fn cached_node(&mut self, id: NodeId) -> Node<'a> {
+        match self.last {
+            Some((last, node)) if last == id => node,
+            _ => {
+                let node = self.node(id);
+                self.last = Some((id, node));
+                node
+            }
+        }
+    }
+}
+
+/// Returns the single byte representation of `c` in the FSTs, if it is one of
+/// the characters which can be stored: Bengali, printable ASCII, ZWNJ or ZWJ.
+pub(crate) fn encode(c: char) -> Option<u8> {
+    match c {
+        '\u{200C}' | '\u{200D}' | '\u{0980}'..='\u{09FF}' | ' '..='~' => Some(c as u8),
+        _ => None,
+    }
+}
+
+/// Reverses the single byte representation of the characters in the FST
+/// (`c as u8`), assuming they are Bengali, ASCII, ZWNJ or ZWJ.
+fn decode(byte: u8) -> char {
+    match byte {
+        0x0C => '\u{200C}',
+        0x0D => '\u{200D}',
+        0x80.. => char::from_u32(0x0900 + u32::from(byte)).unwrap(),
+        _ => char::from(byte),
+    }
+}
+
+/// Hasher for the step keys of the arena, which are small integers and not
+/// controlled by an attacker, so the default SipHash is not needed.
+#[derive(Default)]
+struct StepHasher(u64);
+
+impl Hasher for StepHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.write_u64(u64::from(byte));
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n ^ (n >> 29)).wrapping_mul(0x517c_c1b7_2722_0a95);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0 ^ (self.0 >> 32)
     }
 }
 
@@ -165,33 +520,176 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encode() {
+        for c in ['a', '~', 'ক', '\u{09FF}', '\u{200C}', '\u{200D}'] {
+            assert_eq!(decode(encode(c).unwrap()), c);
+        }
+
+        assert_eq!(encode('\n'), None);
+        assert_eq!(encode('é'), None);
+        assert_eq!(encode('अ'), None);
+    }
+
     #[test]
     fn test_find_matching_node() {
         let fst = FstTree::from_strings(vec!["ক", "কখ", "কখগঘঙচছ"]);
+        let mut arena = fst.arena(&Budget::default());
+
+        let n1 = arena.matching_node("ক").unwrap();
 
-        let n1 = fst.matching_node("ক").unwrap();
+        let n2 = arena.get_matching_node(n1, "খ").unwrap();
 
-        let n2 = n1.get_matching_node("খ").unwrap();
+        _ = arena.get_matching_node(n2, "গঘ").unwrap();
 
-        _ = n2.get_matching_node("গঘ").unwrap();
+        _ = arena.matching_node("কখগঘ").unwrap();
 
-        _ = fst.matching_node("কখগঘ").unwrap();
+        assert_eq!(arena.get_matching_node(n2, "গঙ"), None);
+        assert_eq!(arena.get_matching_node(n2, ""), Some(n2));
+    }
+
+    #[test]
+    fn test_transitions() {
+        let fst = FstTree::from_strings(vec!["ক", "কখ", "কৗ", "ক\u{200C}ষ", "গ"]);
+        let mut arena = fst.arena(&Budget::default());
+
+        let inputs =
+            |arena: &FstArena<_>, id| arena.transitions(id).map(|t| t.input).collect::<Vec<_>>();
+
+        assert_eq!(inputs(&arena, arena.root()), ['ক', 'গ']);
+
+        let n1 = arena.matching_node("ক").unwrap();
+        assert_eq!(inputs(&arena, n1), ['\u{200C}', 'খ', 'ৗ']);
+
+        let words = arena
+            .transitions(n1)
+            .filter_map(|t| arena.follow(n1, t))
+            .collect::<Vec<_>>();
+        assert_eq!(arena.get_word(words[1]), Some("কখ".to_string()));
+        assert_eq!(arena.get_word(words[2]), Some("কৗ".to_string()));
+        assert_eq!(arena.get_matching_node(n1, "খ"), Some(words[1]));
+
+        let n2 = arena.matching_node("ক\u{200C}ষ").unwrap();
+        assert_eq!(inputs(&arena, n2), []);
     }
 
     #[test]
     fn test_get_word() {
         let trie = FstTree::from_strings(vec!["ক", "কখ", "কখগঘঙচছ"]);
+        let mut arena = trie.arena(&Budget::default());
+
+        let n1 = arena.matching_node("ক").unwrap();
+        assert_eq!(arena.get_word(n1), Some("ক".to_string()));
+
+        let n2 = arena.get_matching_node(n1, "খ").unwrap();
+        assert_eq!(arena.get_word(n2), Some("কখ".to_string()));
 
-        let n1 = trie.matching_node("ক").unwrap();
-        assert_eq!(n1.clone().get_word(), Some("ক".to_string()));
+        let n3 = arena.get_matching_node(n2, "গঘ").unwrap();
+        assert_eq!(arena.get_word(n3), None);
 
-        let n2 = n1.get_matching_node("খ").unwrap();
-        assert_eq!(n2.clone().get_word(), Some("কখ".to_string()));
+        let n4 = arena.matching_node("কখগঘ").unwrap();
+        assert_eq!(arena.get_word(n4), None);
 
-        let n3 = n2.get_matching_node("গঘ").unwrap();
-        assert_eq!(n3.get_word(), None);
+        let n5 = arena.get_matching_node(n3, "ঙ").unwrap();
+        let n6 = arena.get_matching_node(n5, "চছ").unwrap();
+        assert_eq!(arena.get_word(n6), Some("কখগঘঙচছ".to_string()));
+    }
 
-        let n4 = trie.matching_node("কখগঘ").unwrap();
-        assert_eq!(n4.get_word(), None);
+    #[test]
+    fn test_same_word_same_node() {
+        let trie = FstTree::from_strings(vec!["কখগ", "কখঘ", "খগ"]);
+        let mut arena = trie.arena(&Budget::default());
+
+        let n1 = arena.matching_node("কখ").unwrap();
+        let n2 = arena.matching_node("ক").unwrap();
+        assert_eq!(arena.get_matching_node(n2, "খ"), Some(n1));
+
+        // Both words end in the same FST state, but they are different nodes
+        let n3 = arena.get_matching_node(n1, "গ").unwrap();
+        let n4 = arena.matching_node("খগ").unwrap();
+        assert_ne!(n3, n4);
+
+        // A failed walk doesn't leave any steps behind
+        assert_eq!(arena.get_matching_node(n2, "খঙ"), None);
+        assert_eq!(arena.matching_node("কখগ"), Some(n3));
+    }
+
+    #[test]
+    fn test_budget() {
+        let trie = FstTree::from_strings(vec!["কখগঘঙ"]);
+        let budget = Budget {
+            max_nodes: Some(3),
+            ..Default::default()
+        };
+        let mut arena = trie.arena(&budget);
+
+        let n1 = arena.matching_node("কখ").unwrap();
+        assert!(!arena.exhausted());
+
+        // The walk runs out of the budget midway
+        assert_eq!(arena.get_matching_node(n1, "গঘ"), None);
+        assert!(arena.exhausted());
+        assert_eq!(arena.matching_node("কখগ"), None);
+    }
+
+    #[test]
+    fn test_intersect() {
+        let trie = FstTree::from_strings(vec!["abc", "abd", "ac", "bc", "ক\u{200C}খ"]);
+        let mut arena = trie.arena(&Budget::default());
+        let mut matches = Vec::new();
+
+        let automaton = FstTree::from_strings(vec!["bc", "c", "cd", "d"]);
+        let n1 = arena.matching_node("a").unwrap();
+        arena.intersect(n1, &automaton.automaton(b"").unwrap(), &mut matches);
+        let mut words = matches
+            .iter()
+            .map(|&(n, _)| arena.get_word(n).unwrap())
+            .collect::<Vec<_>>();
+        words.sort();
+        assert_eq!(words, ["abc", "ac"]);
+
+        // Only the part after the prefix is matched
+        matches.clear();
+        arena.intersect(n1, &automaton.automaton(b"b").unwrap(), &mut matches);
+        assert_eq!(matches, [(arena.matching_node("ac").unwrap(), 0)]);
+        assert!(automaton.automaton(b"x").is_none());
+
+        // The characters are decoded back from their single byte representation
+        matches.clear();
+        let automaton = FstTree::from_strings(vec!["\u{200C}"]);
+        let n2 = arena.matching_node("ক").unwrap();
+        arena.intersect(n2, &automaton.automaton(b"").unwrap(), &mut matches);
+        let n3 = arena.get_matching_node(matches[0].0, "খ").unwrap();
+        assert_eq!(arena.get_word(n3).unwrap(), "ক\u{200C}খ");
+    }
+
+    #[test]
+    fn test_intersect_weights() {
+        let trie = FstTree::from_strings(vec!["ab", "ac", "b"]);
+        let mut arena = trie.arena(&Budget::default());
+        let mut matches = Vec::new();
+
+        let mut map = fst::MapBuilder::memory();
+        for (key, weight) in [("1ab", 3), ("1ac", 0), ("1b", 7), ("2b", 1)] {
+            map.insert(key, weight).unwrap();
+        }
+        let automaton = FstTree::from_fst(map.into_inner().unwrap());
+
+        // The weights are the values of the whole keys, prefix included
+        let root = arena.root();
+        arena.intersect(root, &automaton.automaton(b"1").unwrap(), &mut matches);
+        let mut weights = matches
+            .iter()
+            .map(|&(n, weight)| (arena.get_word(n).unwrap(), weight))
+            .collect::<Vec<_>>();
+        weights.sort();
+        assert_eq!(
+            weights,
+            [
+                ("ab".to_owned(), 3),
+                ("ac".to_owned(), 0),
+                ("b".to_owned(), 7)
+            ]
+        );
     }
 }
@@ -0,0 +1,139 @@
+use once_cell::sync::Lazy;
+
+use crate::{avro::PatternSet, scheme, Budget, Scheme, Suggestions};
+
+/// The ISO 15919 patterns of Bengali, whose keys are the ASCII forms of the
+/// letters given by [`Iso15919::normalize`].
+static PATTERNS: Lazy<PatternSet> = Lazy::new(|| {
+    PatternSet::from_regex_json(
+        include_str!("../data/source-iso15919-patterns.json"),
+        include_str!("../data/source-common-patterns.txt"),
+    )
+    .expect("Invalid ISO 15919 patterns")
+});
+
+/// The ISO 15919 romanization of Bengali, like `bāṅlā` for বাংলা, typed
+/// with either precomposed or combining diacritics.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Iso15919;
+
+impl Iso15919 {
+    pub fn new() -> Self {
+        Iso15919
+    }
+
+    /// Suggests the dictionary words that `input` is the ISO 15919
+    /// romanization of.
+    pub fn suggest(&self, input: &str) -> Vec<String> {
+        self.suggest_with_budget(input, &Budget::default()).words
+    }
+
+    /// Like [`Iso15919::suggest`], with the truncated words of
+    /// [`Scheme::suggest_with_budget`] when `budget` runs out.
+    pub fn suggest_with_budget(&self, input: &str, budget: &Budget) -> Suggestions {
+        scheme::suggest_with_budget(self, input, budget)
+    }
+}
+
+impl Scheme for Iso15919 {
+    /// Spells the letters in ASCII, their diacritics following them as
+    /// symbols: `ā` is `a_`, `ṭ` is `t.`, `ṅ` is `n^`, `ñ` is `n~`, `ś` is
+    /// `s'`, `r̥` is `r0` and `m̐` is `m@`. Everything else is dropped.
+    fn normalize(&self, input: &str) -> String {
+        let mut keys = String::new();
+
+        for c in input.trim().chars() {
+            if c.is_ascii_alphabetic() {
+                keys.push(c.to_ascii_lowercase());
+            } else if let Some((base, mark)) = decompose(c) {
+                keys.push(base);
+                keys.push(mark);
+            } else if let Some(mark) = mark(c) {
+                keys.push(mark);
+            }
+        }
+
+        keys
+    }
+
+    fn patterns(&self) -> &PatternSet {
+        &PATTERNS
+    }
+}
+
+/// Splits the precomposed letter `c` of ISO 15919 into its base letter and
+/// the symbol of its diacritic.
+fn decompose(c: char) -> Option<(char, char)> {
+    let decomposed = match c {
+        'ā' | 'Ā' => ('a', '_'),
+        'ī' | 'Ī' => ('i', '_'),
+        'ū' | 'Ū' => ('u', '_'),
+        'ē' | 'Ē' => ('e', '_'),
+        'ō' | 'Ō' => ('o', '_'),
+        'ṭ' | 'Ṭ' => ('t', '.'),
+        'ḍ' | 'Ḍ' => ('d', '.'),
+        'ṇ' | 'Ṇ' => ('n', '.'),
+        'ṣ' | 'Ṣ' => ('s', '.'),
+        'ṛ' | 'Ṛ' => ('r', '.'),
+        'ḥ' | 'Ḥ' => ('h', '.'),
+        'ṅ' | 'Ṅ' => ('n', '^'),
+        'ṁ' | 'Ṁ' => ('m', '^'),
+        'ẏ' | 'Ẏ' => ('y', '^'),
+        'ñ' | 'Ñ' => ('n', '~'),
+        'ś' | 'Ś' => ('s', '\''),
+        _ => return None,
+    };
+
+    Some(decomposed)
+}
+
+/// Returns the symbol of the combining diacritic `c`.
+fn mark(c: char) -> Option<char> {
+    let mark = match c {
+        '\u{0304}' => '_',
+        '\u{0323}' => '.',
+        '\u{0307}' => '^',
+        '\u{0303}' => '~',
+        '\u{0301}' => '\'',
+        '\u{0325}' => '0',
+        '\u{0310}' => '@',
+        _ => return None,
+    };
+
+    Some(mark)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let iso = Iso15919::new();
+
+        assert_eq!(iso.normalize("bāṅlā"), "ba_n^la_");
+        // Combining diacritics
+        assert_eq!(iso.normalize("ba\u{0304}n\u{0307}la\u{0304}"), "ba_n^la_");
+        assert_eq!(iso.normalize("r\u{0325}\u{0304}"), "r0_");
+        assert_eq!(iso.normalize(" Śikṣā, "), "s'iks.a_");
+    }
+
+    #[test]
+    fn test_iso15919() {
+        let iso = Iso15919::new();
+
+        assert_eq!(iso.suggest("bāṁlā"), ["বাংলা"]);
+        assert_eq!(iso.suggest("āmi"), ["আমি"]);
+        assert_eq!(iso.suggest("bhāi"), ["ভাই"]);
+        assert_eq!(iso.suggest("kathā"), ["কথা"]);
+        assert_eq!(iso.suggest("śikṣā"), ["শিক্ষা"]);
+        assert_eq!(iso.suggest("dūṣaṇa"), ["দূষণ"]);
+        assert_eq!(iso.suggest("barṣā"), ["বরষা", "বর্ষা"]);
+        assert_eq!(iso.suggest("r\u{0325}tu"), ["ঋতু"]);
+        // The common suffixes of Avro, like the phola of প্যাড়া, are tried too
+        assert!(iso.suggest("pāṛā").contains(&"পা\u{09DC}া".to_owned()));
+        assert_eq!(iso.suggest("ẏ"), ["\u{09DF}"]);
+        // `x` is not a letter of ISO 15919
+        assert!(iso.suggest("xa").is_empty());
+    }
+}
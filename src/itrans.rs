@@ -0,0 +1,80 @@
+use once_cell::sync::Lazy;
+
+use crate::{avro::PatternSet, scheme, Budget, Scheme, Suggestions};
+
+/// The ITRANS patterns of Bengali.
+static PATTERNS: Lazy<PatternSet> = Lazy::new(|| {
+    PatternSet::from_regex_json(
+        include_str!("../data/source-itrans-patterns.json"),
+        include_str!("../data/source-common-patterns.txt"),
+    )
+    .expect("Invalid ITRANS patterns")
+});
+
+/// The ITRANS romanization of Bengali, like `bA.nlA` for বাংলা.
+///
+/// Unlike Avro Phonetic, ITRANS is case sensitive: `t` is ত while `T` is ট,
+/// and `A` is the long আ.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Itrans;
+
+impl Itrans {
+    pub fn new() -> Self {
+        Itrans
+    }
+
+    /// Suggests the dictionary words that `input` is the ITRANS spelling of.
+    pub fn suggest(&self, input: &str) -> Vec<String> {
+        self.suggest_with_budget(input, &Budget::default()).words
+    }
+
+    /// Like [`Itrans::suggest`], with the truncated words of
+    /// [`Scheme::suggest_with_budget`] when `budget` runs out.
+    pub fn suggest_with_budget(&self, input: &str, budget: &Budget) -> Suggestions {
+        scheme::suggest_with_budget(self, input, budget)
+    }
+}
+
+impl Scheme for Itrans {
+    /// Keeps the letters and the `.`, `~` and `^` of the ITRANS keys, in
+    /// their case.
+    fn normalize(&self, input: &str) -> String {
+        input
+            .trim()
+            .chars()
+            .filter(|&c| c.is_ascii_alphanumeric() || matches!(c, '.' | '~' | '^'))
+            .collect()
+    }
+
+    fn patterns(&self) -> &PatternSet {
+        &PATTERNS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_itrans() {
+        let itrans = Itrans::new();
+
+        assert_eq!(itrans.suggest("bA.nlA"), ["বাংলা"]);
+        assert_eq!(itrans.suggest("bAMlA"), ["বাংলা"]);
+        assert_eq!(itrans.suggest("Ami"), ["আমি"]);
+        assert_eq!(itrans.suggest("bhAi"), ["ভাই"]);
+        assert_eq!(itrans.suggest("kathA"), ["কথা"]);
+        assert_eq!(itrans.suggest("svAdhInatA"), ["স্বাধীনতা"]);
+        assert_eq!(itrans.suggest("dUShaN"), ["দূষণ"]);
+        assert_eq!(itrans.suggest("shikShA"), ["শিক্ষা"]);
+        assert_eq!(itrans.suggest("Dh.D"), Vec::<String>::new());
+
+        // The case tells the letters apart
+        assert!(itrans.suggest("TAkA").contains(&"টাকা".to_owned()));
+        assert!(!itrans.suggest("tAkA").contains(&"টাকা".to_owned()));
+        assert_eq!(
+            itrans.segment("svAdhInatA"),
+            (vec!["s", "v", "A", "dh", "I", "n", "a", "t", "A"], "")
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+use serde::Deserialize;
+
+use crate::{bangla, complete::CompletionOptions, segment::is_consonant, Suggestions};
+
+const HASANTA: char = '\u{09CD}';
+const NUKTA: char = '\u{09BC}';
+const E_KAR: char = '\u{09C7}';
+const AA_KAR: char = '\u{09BE}';
+const AU_LENGTH_MARK: char = '\u{09D7}';
+
+/// A keyboard layout typing Bangla directly, like Probhat or National, which
+/// turns the keystrokes typed with it into the Bangla text to complete with
+/// [`bangla::suggest`].
+///
+/// Layouts are described in JSON, like
+///
+/// ```json
+/// {
+///   "name": "National",
+///   "preBaseKars": true,
+///   "keys": { "j": "ক", "d": "ি", "g": "্", "gd": "ই" }
+/// }
+/// ```
+///
+/// where `keys` maps the keystrokes to the text they type. A sequence of
+/// several keystrokes types its own text, the longest sequence typed winning.
+/// The keystrokes which are not in the layout are kept as they are typed.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    pub name: String,
+    /// Whether the pre-base kars (ি, ে and ৈ) are typed before the consonant
+    /// they follow in the text, in the order they are seen.
+    pub pre_base_kars: bool,
+    keys: HashMap<String, String>,
+    /// The number of keystrokes of the longest sequence in `keys`.
+    longest: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct LayoutSource {
+    name: String,
+    #[serde(default)]
+    pre_base_kars: bool,
+    keys: HashMap<String, String>,
+}
+
+impl Layout {
+    /// Loads a layout from its JSON description.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let source: LayoutSource = serde_json::from_str(json)?;
+        let longest = source
+            .keys
+            .keys()
+            .map(|keystrokes| keystrokes.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        Ok(Layout {
+            name: source.name,
+            pre_base_kars: source.pre_base_kars,
+            keys: source.keys,
+            longest,
+        })
+    }
+
+    /// The Probhat layout.
+    pub fn probhat() -> Self {
+        Layout::from_json(include_str!("../data/layout-probhat.json"))
+            .expect("Invalid Probhat layout")
+    }
+
+    /// The National (Jatiya) layout of Bangladesh, typing the pre-base kars
+    /// before their consonant and the independent vowels with the hasanta key
+    /// before their sign, like `gd` for ই.
+    pub fn national() -> Self {
+        Layout::from_json(include_str!("../data/layout-national.json"))
+            .expect("Invalid National layout")
+    }
+
+    /// Converts the `keystrokes` typed with the layout to Bangla text.
+    pub fn convert(&self, keystrokes: &str) -> String {
+        let typed = self.map_keys(keystrokes);
+
+        if self.pre_base_kars {
+            reorder_pre_base_kars(&typed, false)
+        } else {
+            typed
+        }
+    }
+
+    /// Suggests the dictionary words completing the `keystrokes` typed with
+    /// the layout.
+    pub fn suggest(&self, keystrokes: &str) -> Vec<String> {
+        bangla::suggest(&self.convert(keystrokes))
+    }
+
+    /// Like [`Layout::suggest`], but completes with the words allowed by
+    /// `options`.
+    pub fn suggest_with(&self, keystrokes: &str, options: &CompletionOptions) -> Suggestions {
+        bangla::suggest_with(&self.convert(keystrokes), options)
+    }
+
+    /// Maps the `keystrokes` to their text, from the longest sequence in the
+    /// layout at every point.
+    fn map_keys(&self, keystrokes: &str) -> String {
+        let mut text = String::new();
+        let mut remaining = keystrokes;
+
+        while let Some(first) = remaining.chars().next() {
+            match longest_match(remaining, &self.keys, self.longest) {
+                Some((end, typed)) => {
+                    text.push_str(typed);
+                    remaining = &remaining[end..];
+                }
+                None => {
+                    text.push(first);
+                    remaining = &remaining[first.len_utf8()..];
+                }
+            }
+        }
+
+        text
+    }
+}
+
+/// Finds the longest key of `map`, of at most `longest` characters, which
+/// `text` starts with, and returns its length in bytes and its value.
+pub(crate) fn longest_match<'m, K, V>(
+    text: &str,
+    map: &'m HashMap<K, V>,
+    longest: usize,
+) -> Option<(usize, &'m V)>
+where
+    K: Borrow<str> + Eq + Hash,
+{
+    let ends: Vec<_> = text
+        .char_indices()
+        .map(|(i, c)| i + c.len_utf8())
+        .take(longest)
+        .collect();
+
+    ends.into_iter()
+        .rev()
+        .find_map(|end| Some((end, map.get(&text[..end])?)))
+}
+
+fn is_pre_base_kar(c: char) -> bool {
+    matches!(c, '\u{09BF}' | '\u{09C7}' | '\u{09C8}')
+}
+
+/// Moves the pre-base kars of the visually ordered `text` after the consonant
+/// or conjunct they are typed before. A kar with no consonant after it stays
+/// where it is typed.
+///
+/// An e-kar with an aa-kar or the au length mark after its consonant makes
+/// the o-kar or the au-kar.
+///
+/// When `stray_only` is set, the kars after a consonant are taken to be in
+/// their place already, and only the others are moved.
+pub(crate) fn reorder_pre_base_kars(text: &str, stray_only: bool) -> String {
+    let mut reordered = String::new();
+    // The kar waiting for its consonant and the consonants seen after it
+    let mut pending: Option<(char, String)> = None;
+
+    for c in text.chars() {
+        if let Some((kar, cluster)) = &mut pending {
+            let last = cluster.chars().last();
+
+            let extends = match last {
+                None => is_consonant(c),
+                Some(HASANTA) => is_consonant(c),
+                Some(_) => c == HASANTA || c == NUKTA,
+            };
+            if extends {
+                cluster.push(c);
+                continue;
+            }
+
+            let kar = *kar;
+            reordered.push_str(cluster);
+            pending = None;
+
+            if last.is_some() && kar == E_KAR {
+                match c {
+                    AA_KAR => {
+                        reordered.push('\u{09CB}');
+                        continue;
+                    }
+                    AU_LENGTH_MARK => {
+                        reordered.push('\u{09CC}');
+                        continue;
+                    }
+                    _ => {}
+                }
+            }
+            reordered.push(kar);
+        }
+
+        let placed = stray_only
+            && reordered
+                .chars()
+                .last()
+                .is_some_and(|last| is_consonant(last) || last == NUKTA);
+
+        if is_pre_base_kar(c) && !placed {
+            pending = Some((c, String::new()));
+        } else {
+            reordered.push(c);
+        }
+    }
+
+    if let Some((kar, cluster)) = pending {
+        reordered.push_str(&cluster);
+        reordered.push(kar);
+    }
+
+    reordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Order;
+
+    #[test]
+    fn test_probhat() {
+        let probhat = Layout::probhat();
+
+        assert_eq!(probhat.convert("baLla"), "বাংলা");
+        assert_eq!(probhat.convert("km/piUtar"), "কম্পিউটার");
+        assert_eq!(probhat.convert("vmi"), "আমি");
+        // Pre-base kars are typed after their consonant
+        assert_eq!(probhat.convert("k[k"), "কেক");
+        assert_eq!(probhat.convert("1, 2?"), "১, ২?");
+
+        assert!(probhat.suggest("vma").contains(&"আমার".to_owned()));
+    }
+
+    #[test]
+    fn test_national() {
+        let national = Layout::national();
+
+        // Pre-base kars are typed before their consonant or conjunct
+        assert_eq!(national.convert("dj"), "কি");
+        assert_eq!(national.convert("cjj"), "কেক");
+        assert_eq!(national.convert("dngkgv"), "স্ত্রি");
+        assert_eq!(national.convert("dngkz"), "স্ত্রি");
+        // The e-kar and the aa-kar or au length mark around the consonant
+        assert_eq!(national.convert("cMf"), "শো");
+        assert_eq!(national.convert("cMA"), "শৌ");
+        // A kar with no consonant after it stays where it is typed
+        assert_eq!(national.convert("jd"), "কি");
+        assert_eq!(national.convert("jdd"), "কিি");
+        assert_eq!(national.convert("Cdb"), "ৈনি");
+        // The independent vowels
+        assert_eq!(national.convert("gfdm"), "আমি");
+        assert_eq!(national.convert("gdbd"), "ইনি");
+        assert_eq!(national.convert("Fgs"), "অউ");
+        // The signs and the phola
+        assert_eq!(national.convert("hfQVf"), "বাংলা");
+        assert_eq!(national.convert("ls|J"), "দুঃখ");
+        assert_eq!(national.convert("yf&l"), "চাঁদ");
+        assert_eq!(national.convert("hZfj"), "ব্যাক");
+
+        assert!(national.suggest("gfmf").contains(&"আমার".to_owned()));
+        let options = CompletionOptions {
+            order: Order::Alphabetical,
+            max_results: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(
+            national.suggest_with("gfmf", &options).words,
+            ["আমাকে", "আমাগো", "আমাজন"]
+        );
+    }
+
+    #[test]
+    fn test_from_json() {
+        let layout = Layout::from_json(
+            r#"{ "name": "Test", "keys": { "k": "ক", "kh": "খ", "a": "া", "i": "ি" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(layout.name, "Test");
+        assert!(!layout.pre_base_kars);
+        assert_eq!(layout.convert("khak"), "খাক");
+        assert_eq!(layout.convert("kix"), "কিx");
+
+        assert!(Layout::from_json(r#"{ "name": "Test" }"#).is_err());
+        assert!(Layout::from_json(r#"{ "name": "Test", "keys": {}, "kars": true }"#).is_err());
+    }
+}
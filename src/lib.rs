@@ -7,4 +7,17 @@ static WORDS: Lazy<FstTree<&[u8]>> = Lazy::new(|| FstTree::from_fst(include_byte
 
 pub mod avro;
 pub mod bangla;
+mod budget;
+mod complete;
+pub mod conjunct;
 mod fst;
+pub mod iso15919;
+pub mod itrans;
+pub mod layouts;
+pub mod numerals;
+mod scheme;
+pub mod segment;
+
+pub use budget::{Budget, CancelToken, Suggestions};
+pub use complete::{CompletionOptions, Order};
+pub use scheme::Scheme;
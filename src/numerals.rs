@@ -0,0 +1,305 @@
+/// The words of the numbers below a hundred.
+const WORDS: [&str; 100] = [
+    "শূন্য",
+    "এক",
+    "দুই",
+    "তিন",
+    "চার",
+    "পাঁচ",
+    "ছয়",
+    "সাত",
+    "আট",
+    "নয়",
+    "দশ",
+    "এগারো",
+    "বারো",
+    "তেরো",
+    "চৌদ্দ",
+    "পনেরো",
+    "ষোলো",
+    "সতেরো",
+    "আঠারো",
+    "উনিশ",
+    "বিশ",
+    "একুশ",
+    "বাইশ",
+    "তেইশ",
+    "চব্বিশ",
+    "পঁচিশ",
+    "ছাব্বিশ",
+    "সাতাশ",
+    "আটাশ",
+    "ঊনত্রিশ",
+    "ত্রিশ",
+    "একত্রিশ",
+    "বত্রিশ",
+    "তেত্রিশ",
+    "চৌত্রিশ",
+    "পঁয়ত্রিশ",
+    "ছত্রিশ",
+    "সাঁইত্রিশ",
+    "আটত্রিশ",
+    "ঊনচল্লিশ",
+    "চল্লিশ",
+    "একচল্লিশ",
+    "বিয়াল্লিশ",
+    "তেতাল্লিশ",
+    "চুয়াল্লিশ",
+    "পঁয়তাল্লিশ",
+    "ছেচল্লিশ",
+    "সাতচল্লিশ",
+    "আটচল্লিশ",
+    "ঊনপঞ্চাশ",
+    "পঞ্চাশ",
+    "একান্ন",
+    "বাহান্ন",
+    "তিপ্পান্ন",
+    "চুয়ান্ন",
+    "পঞ্চান্ন",
+    "ছাপ্পান্ন",
+    "সাতান্ন",
+    "আটান্ন",
+    "ঊনষাট",
+    "ষাট",
+    "একষট্টি",
+    "বাষট্টি",
+    "তেষট্টি",
+    "চৌষট্টি",
+    "পঁয়ষট্টি",
+    "ছেষট্টি",
+    "সাতষট্টি",
+    "আটষট্টি",
+    "ঊনসত্তর",
+    "সত্তর",
+    "একাত্তর",
+    "বাহাত্তর",
+    "তিয়াত্তর",
+    "চুয়াত্তর",
+    "পঁচাত্তর",
+    "ছিয়াত্তর",
+    "সাতাত্তর",
+    "আটাত্তর",
+    "ঊনআশি",
+    "আশি",
+    "একাশি",
+    "বিরাশি",
+    "তিরাশি",
+    "চুরাশি",
+    "পঁচাশি",
+    "ছিয়াশি",
+    "সাতাশি",
+    "অষ্টাশি",
+    "ঊননব্বই",
+    "নব্বই",
+    "একানব্বই",
+    "বিরানব্বই",
+    "তিরানব্বই",
+    "চুরানব্বই",
+    "পঁচানব্বই",
+    "ছিয়ানব্বই",
+    "সাতানব্বই",
+    "আটানব্বই",
+    "নিরানব্বই",
+];
+
+/// The words of the first ten ordinals.
+const ORDINAL_WORDS: [&str; 10] = [
+    "প্রথম",
+    "দ্বিতীয়",
+    "তৃতীয়",
+    "চতুর্থ",
+    "পঞ্চম",
+    "ষষ্ঠ",
+    "সপ্তম",
+    "অষ্টম",
+    "নবম",
+    "দশম",
+];
+
+/// The units of the Indian numbering system above a hundred, largest first.
+const UNITS: [(u64, &str); 3] = [(10_000_000, "কোটি"), (100_000, "লাখ"), (1_000, "হাজার")];
+
+/// The English ordinal suffixes which can be typed after a number.
+const ORDINAL_SUFFIXES: [&str; 4] = ["st", "nd", "rd", "th"];
+
+/// Converts the ASCII digits of `input` to Bengali digits.
+pub fn to_bengali_digits(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c.to_digit(10) {
+            Some(digit) if c.is_ascii_digit() => char::from_u32(0x09E6 + digit).unwrap(),
+            _ => c,
+        })
+        .collect()
+}
+
+/// Spells `number` in words, grouping it in crores, lakhs and thousands,
+/// e.g. 123 is একশ তেইশ and 150000 is এক লাখ পঞ্চাশ হাজার.
+pub fn to_words(number: u64) -> String {
+    if number == 0 {
+        return WORDS[0].to_owned();
+    }
+
+    let mut words = Vec::new();
+    let mut rest = number;
+
+    for (unit, name) in UNITS {
+        let count = rest / unit;
+        rest %= unit;
+
+        if count == 0 {
+            continue;
+        }
+
+        // Only crores are counted beyond a hundred, like একশ কোটি
+        if unit == 10_000_000 {
+            words.push(format!("{} {name}", to_words(count)));
+        } else {
+            words.push(format!("{} {name}", WORDS[count as usize]));
+        }
+    }
+
+    if rest >= 100 {
+        words.push(format!("{}শ", WORDS[(rest / 100) as usize]));
+        rest %= 100;
+    }
+
+    if rest > 0 {
+        words.push(WORDS[rest as usize].to_owned());
+    }
+
+    words.join(" ")
+}
+
+/// Writes `number` as an ordinal in digits, e.g. ১ম, ২য়, ৪র্থ or ১১তম.
+pub fn to_ordinal(number: u64) -> Option<String> {
+    let suffix = match number {
+        0 => return None,
+        1 | 5 | 7..=10 => "ম",
+        2 | 3 => "য়",
+        4 => "র্থ",
+        6 => "ষ্ঠ",
+        _ => "তম",
+    };
+
+    Some(to_bengali_digits(&number.to_string()) + suffix)
+}
+
+/// Spells `number` as an ordinal in words, e.g. প্রথম or এগারোতম.
+pub fn to_ordinal_words(number: u64) -> Option<String> {
+    match number {
+        0 => None,
+        1..=10 => Some(ORDINAL_WORDS[number as usize - 1].to_owned()),
+        _ => Some(to_words(number) + "তম"),
+    }
+}
+
+/// Suggests the Bengali forms of a number typed in ASCII digits: in Bengali
+/// digits and in words, or as an ordinal when it ends with an English
+/// ordinal suffix like `1st` or `4th`.
+///
+/// Returns nothing when `input` is not a number.
+pub fn suggest(input: &str) -> Vec<String> {
+    let input = input.trim();
+    let digits = input.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let suffix = &input[digits.len()..];
+
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Vec::new();
+    }
+
+    // Numbers with leading zeros, like codes, and huge numbers aren't spelled
+    let number = digits
+        .parse::<u64>()
+        .ok()
+        .filter(|_| digits.len() == 1 || !digits.starts_with('0'));
+
+    if suffix.is_empty() {
+        let mut suggestions = vec![to_bengali_digits(digits)];
+        suggestions.extend(number.map(to_words));
+        return suggestions;
+    }
+
+    if !ORDINAL_SUFFIXES.contains(&suffix.to_ascii_lowercase().as_str()) {
+        return Vec::new();
+    }
+
+    number
+        .into_iter()
+        .flat_map(|n| to_ordinal(n).into_iter().chain(to_ordinal_words(n)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bengali_digits() {
+        assert_eq!(to_bengali_digits("0123456789"), "০১২৩৪৫৬৭৮৯");
+        assert_eq!(to_bengali_digits("12:30"), "১২:৩০");
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(to_words(0), "শূন্য");
+        assert_eq!(to_words(7), "সাত");
+        assert_eq!(to_words(23), "তেইশ");
+        assert_eq!(to_words(99), "নিরানব্বই");
+        assert_eq!(to_words(100), "একশ");
+        assert_eq!(to_words(123), "একশ তেইশ");
+        assert_eq!(to_words(1000), "এক হাজার");
+        assert_eq!(to_words(2024), "দুই হাজার চব্বিশ");
+        assert_eq!(to_words(150_000), "এক লাখ পঞ্চাশ হাজার");
+        assert_eq!(to_words(10_000_000), "এক কোটি");
+        assert_eq!(
+            to_words(1_234_567_890),
+            "একশ তেইশ কোটি পঁয়তাল্লিশ লাখ সাতষট্টি হাজার আটশ নব্বই"
+        );
+    }
+
+    #[test]
+    fn test_ordinals() {
+        let ordinals = (1..=12).map(|n| to_ordinal(n).unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            ordinals,
+            [
+                "১ম",
+                "২য়",
+                "৩য়",
+                "৪র্থ",
+                "৫ম",
+                "৬ষ্ঠ",
+                "৭ম",
+                "৮ম",
+                "৯ম",
+                "১০ম",
+                "১১তম",
+                "১২তম"
+            ]
+        );
+        assert_eq!(to_ordinal(0), None);
+
+        assert_eq!(to_ordinal_words(1).unwrap(), "প্রথম");
+        assert_eq!(to_ordinal_words(6).unwrap(), "ষষ্ঠ");
+        assert_eq!(to_ordinal_words(10).unwrap(), "দশম");
+        assert_eq!(to_ordinal_words(11).unwrap(), "এগারোতম");
+        assert_eq!(to_ordinal_words(0), None);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        assert_eq!(suggest("123"), ["১২৩", "একশ তেইশ"]);
+        assert_eq!(suggest("0"), ["০", "শূন্য"]);
+        assert_eq!(suggest("007"), ["০০৭"]);
+        assert_eq!(suggest("99999999999999999999"), ["৯৯৯৯৯৯৯৯৯৯৯৯৯৯৯৯৯৯৯৯"]);
+        assert_eq!(suggest("1st"), ["১ম", "প্রথম"]);
+        assert_eq!(suggest("2nd"), ["২য়", "দ্বিতীয়"]);
+        assert_eq!(suggest("21ST"), ["২১তম", "একুশতম"]);
+        assert_eq!(suggest("0th"), Vec::<String>::new());
+        assert_eq!(suggest("12ab"), Vec::<String>::new());
+        assert_eq!(suggest("ami"), Vec::<String>::new());
+        assert_eq!(suggest("1a2"), Vec::<String>::new());
+        assert_eq!(suggest(""), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,214 @@
+use once_cell::sync::Lazy;
+
+use crate::{
+    avro::{
+        rule::{Context, Preceding},
+        PatternSet,
+    },
+    fst::{FstArena, NodeId},
+    Budget, Suggestions, WORDS,
+};
+
+/// A Roman input scheme, which splits the typed input into the patterns of
+/// the scheme and looks up the Bangla transliterations of every pattern to
+/// search the dictionary with.
+///
+/// [`avro::Suggest`](crate::avro::Suggest) is the Avro Phonetic scheme, and
+/// [`Itrans`](crate::itrans::Itrans) and [`Iso15919`](crate::iso15919::Iso15919)
+/// the standard romanizations. Other schemes only need their patterns and how
+/// the input is normalized:
+///
+/// ```
+/// use upodesh::{avro::PatternSet, Scheme};
+///
+/// struct Plain(PatternSet);
+///
+/// impl Scheme for Plain {
+///     fn normalize(&self, input: &str) -> String {
+///         input.trim().to_lowercase()
+///     }
+///
+///     fn patterns(&self) -> &PatternSet {
+///         &self.0
+///     }
+/// }
+///
+/// let json = r#"{
+///     "a": { "transliterate": "[আা]" },
+///     "m": { "transliterate": "ম" },
+///     "r": { "transliterate": "র" }
+/// }"#;
+/// let plain = Plain(PatternSet::from_regex_json(json, "").unwrap());
+///
+/// assert_eq!(plain.suggest("AMAR"), ["আমার"]);
+/// ```
+pub trait Scheme {
+    /// Normalizes the typed `input` into the keys of the patterns, dropping
+    /// what isn't typed with them.
+    fn normalize(&self, input: &str) -> String;
+
+    /// The patterns of the scheme and their transliterations.
+    fn patterns(&self) -> &PatternSet;
+
+    /// Splits the normalized `input` into its patterns, from the longest
+    /// pattern at every point. The input left after the last pattern, if
+    /// any, starts with no pattern.
+    fn segment<'a>(&self, input: &'a str) -> (Vec<&'a str>, &'a str) {
+        let mut segments = Vec::new();
+        let mut remaining = input;
+
+        while let Some((pattern, rest)) = self.patterns().segment(remaining) {
+            segments.push(pattern);
+            remaining = rest;
+        }
+
+        (segments, remaining)
+    }
+
+    /// Suggests the dictionary words that `input` transliterates to in the
+    /// scheme, the best ranked first.
+    fn suggest(&self, input: &str) -> Vec<String> {
+        self.suggest_with_budget(input, &Budget::default()).words
+    }
+
+    /// Like [`Scheme::suggest`], but stops when `budget` runs out. The words
+    /// matching the whole input by then are returned flagged as truncated,
+    /// never the ones matching only its start.
+    fn suggest_with_budget(&self, input: &str, budget: &Budget) -> Suggestions {
+        suggest_with_budget(self, input, budget)
+    }
+}
+
+/// Suggests the dictionary words `input` transliterates to in `scheme`,
+/// stopping when `budget` runs out.
+pub(crate) fn suggest_with_budget<S: Scheme + ?Sized>(
+    scheme: &S,
+    input: &str,
+    budget: &Budget,
+) -> Suggestions {
+    let words = Lazy::force(&WORDS);
+    let mut arena = words.arena(budget);
+
+    let root = arena.root();
+    let matched_nodes = matching_nodes(
+        scheme,
+        &mut arena,
+        root,
+        &scheme.normalize(input),
+        Preceding::Nothing,
+    );
+
+    Suggestions {
+        words: ranked(matched_nodes)
+            .into_iter()
+            .filter_map(|n| arena.get_word(n))
+            .collect(),
+        truncated: arena.exhausted(),
+    }
+}
+
+/// Returns the nodes of the words continuing `from` that match the
+/// normalized `input` in `scheme`, sorted, with their scores.
+///
+/// The score of a node is the sum of the weights of the transliterations
+/// walked to reach it, one per pattern of the input.
+///
+/// Each pattern uses the first rule of its block that holds where it is
+/// matched, the input being `preceding` by the start of the word or the end of
+/// the word it continues.
+pub(crate) fn matching_nodes<S: Scheme + ?Sized, D: AsRef<[u8]>>(
+    scheme: &S,
+    arena: &mut FstArena<'_, D>,
+    from: NodeId,
+    input: &str,
+    mut preceding: Preceding,
+) -> Vec<(NodeId, u64)> {
+    let patterns = scheme.patterns();
+
+    let Some((matched, mut remaining)) = patterns.segment(input) else {
+        return Vec::new();
+    };
+
+    let context = Context {
+        preceding,
+        following: remaining,
+    };
+    let Some(matched_block) = patterns.block_in(matched, &context) else {
+        return Vec::new();
+    };
+    preceding = matched.chars().last().map_or(preceding, Preceding::roman);
+
+    let mut matched_nodes = Vec::new();
+    let mut new_matched_nodes = Vec::new();
+
+    arena.intersect(from, matched_block.automaton(), &mut matched_nodes);
+
+    extend_common_suffixes(patterns, arena, &mut matched_nodes);
+
+    while let Some((new_matched, new_remaining)) = patterns.segment(remaining) {
+        remaining = new_remaining;
+
+        let context = Context {
+            preceding,
+            following: remaining,
+        };
+        let Some(new_matched_block) = patterns.block_in(new_matched, &context) else {
+            // If no patterns match, we can stop here
+            break;
+        };
+        preceding = new_matched
+            .chars()
+            .last()
+            .map_or(preceding, Preceding::roman);
+
+        new_matched_nodes.clear();
+        for &(node, score) in &matched_nodes {
+            let start = new_matched_nodes.len();
+            arena.intersect(node, new_matched_block.automaton(), &mut new_matched_nodes);
+
+            for (_, weight) in &mut new_matched_nodes[start..] {
+                *weight += score;
+            }
+        }
+
+        if new_matched_block.entire_block_optional() {
+            // Entirely optional patterns like "([ওোঅ]|(অ্য)|(য়ো?))?" may not yield any result
+            matched_nodes.extend_from_slice(&new_matched_nodes);
+        } else {
+            std::mem::swap(&mut matched_nodes, &mut new_matched_nodes);
+        }
+
+        extend_common_suffixes(patterns, arena, &mut matched_nodes);
+    }
+
+    matched_nodes
+}
+
+/// Adds the nodes reached by appending the common suffixes (hasanta, phola
+/// etc.) to the matched nodes, and removes the nodes reached more than once
+/// so that they are not walked again and again, keeping their best score.
+fn extend_common_suffixes<D: AsRef<[u8]>>(
+    patterns: &PatternSet,
+    arena: &mut FstArena<'_, D>,
+    nodes: &mut Vec<(NodeId, u64)>,
+) {
+    for i in 0..nodes.len() {
+        let (from, score) = nodes[i];
+
+        for suffix in &patterns.common_suffixes {
+            if let Some(node) = arena.get_matching_node(from, suffix) {
+                nodes.push((node, score));
+            }
+        }
+    }
+
+    nodes.sort_unstable_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)));
+    nodes.dedup_by_key(|&mut (node, _)| node);
+}
+
+/// Orders the matched `nodes` by descending score, keeping the order of the
+/// nodes with the same score.
+pub(crate) fn ranked(mut nodes: Vec<(NodeId, u64)>) -> Vec<NodeId> {
+    nodes.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    nodes.into_iter().map(|(node, _)| node).collect()
+}
@@ -0,0 +1,234 @@
+const HASANTA: char = '\u{09CD}';
+const ZWNJ: char = '\u{200C}';
+const ZWJ: char = '\u{200D}';
+
+/// Returns whether `c` continues the grapheme cluster ending with `prev`
+/// instead of starting a new one.
+///
+/// Vowel signs, hasanta and the other dependent signs attach to the preceding
+/// letter, and a consonant after a hasanta makes a conjunct with it.
+pub(crate) fn extends_cluster(prev: Option<char>, c: char) -> bool {
+    match c {
+        '\u{0981}'..='\u{0983}' | '\u{09BC}' | '\u{09BE}'..='\u{09CD}' | '\u{09D7}' => true,
+        ZWNJ | ZWJ => true,
+        _ => matches!(prev, Some(HASANTA | ZWJ)) && is_consonant(c),
+    }
+}
+
+pub(crate) fn is_consonant(c: char) -> bool {
+    matches!(c, 'ক'..='হ' | '\u{09CE}' | '\u{09DC}'..='\u{09DF}')
+}
+
+fn is_vowel_sign(c: char) -> bool {
+    matches!(c, '\u{09BE}'..='\u{09C4}' | '\u{09C7}'..='\u{09CC}')
+}
+
+/// Returns the number of grapheme clusters in `text`, the letters the user
+/// sees, like `ক্ষ্ম` or `মা`.
+pub fn count_clusters(text: &str) -> usize {
+    grapheme_clusters(text).count()
+}
+
+/// Splits `text` into its grapheme clusters, the letters the cursor moves
+/// over: a consonant or a conjunct with its vowel sign and other signs, like
+/// `ক্ষ্মা` or `র্কে`, an independent vowel with its signs, or any other
+/// character.
+pub fn grapheme_clusters(text: &str) -> GraphemeClusters<'_> {
+    GraphemeClusters { remaining: text }
+}
+
+/// The iterator returned by [`grapheme_clusters`].
+#[derive(Clone, Debug)]
+pub struct GraphemeClusters<'a> {
+    remaining: &'a str,
+}
+
+impl<'a> Iterator for GraphemeClusters<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut chars = self.remaining.char_indices();
+        let (_, first) = chars.next()?;
+
+        let mut prev = first;
+        let end = chars
+            .find(|&(_, c)| {
+                let starts_cluster = !extends_cluster(Some(prev), c);
+                prev = c;
+                starts_cluster
+            })
+            .map_or(self.remaining.len(), |(i, _)| i);
+
+        let (cluster, rest) = self.remaining.split_at(end);
+        self.remaining = rest;
+
+        Some(cluster)
+    }
+}
+
+/// The parts of an orthographic syllable, like `র্ব্যে` = reph, ব,
+/// ya-phala and e-kar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Syllable<'a> {
+    /// The whole syllable, which is a grapheme cluster.
+    pub text: &'a str,
+    /// Whether the syllable starts with a reph, the র্ written above the
+    /// consonant after it.
+    pub reph: bool,
+    /// The consonant or conjunct the syllable is built on, without the reph
+    /// and the ya-phala, or the independent vowel or other character.
+    pub base: &'a str,
+    /// Whether a ya-phala (্য) follows the base.
+    pub ya_phala: bool,
+    /// The vowel sign attached to the base.
+    pub vowel_sign: Option<char>,
+    /// The signs after the vowel sign, like ং, ঁ, a final hasanta or the au
+    /// length mark.
+    pub signs: &'a str,
+}
+
+impl<'a> Syllable<'a> {
+    /// Splits the grapheme cluster `text` into its parts.
+    fn parse(text: &'a str) -> Self {
+        let mut rest = text;
+
+        let reph = rest
+            .strip_prefix("র\u{09CD}")
+            .and_then(|after| after.chars().next())
+            .is_some_and(is_consonant);
+        if reph {
+            rest = &rest["র\u{09CD}".len()..];
+        }
+
+        // The consonants joined by hasantas, with their nuktas and ZWJs
+        let mut chars = rest.char_indices().peekable();
+        let mut base_end = chars.next().map_or(0, |(i, c)| i + c.len_utf8());
+        while let Some((i, c)) = chars.next() {
+            let joined = c == HASANTA && chars.peek().is_some_and(|&(_, c)| is_consonant(c));
+            if joined {
+                let (j, consonant) = chars.next().unwrap_or((i, c));
+                base_end = j + consonant.len_utf8();
+            } else if c == '\u{09BC}' || c == ZWJ {
+                base_end = i + c.len_utf8();
+            } else {
+                break;
+            }
+        }
+        let (mut base, mut rest) = rest.split_at(base_end);
+
+        let ya_phala = base.chars().count() > 1 && base.ends_with("\u{09CD}য");
+        if ya_phala {
+            base = &base[..base.len() - "\u{09CD}য".len()];
+        }
+
+        let vowel_sign = rest.chars().next().filter(|&c| is_vowel_sign(c));
+        if let Some(c) = vowel_sign {
+            rest = &rest[c.len_utf8()..];
+        }
+
+        Syllable {
+            text,
+            reph,
+            base,
+            ya_phala,
+            vowel_sign,
+            signs: rest,
+        }
+    }
+}
+
+/// Splits `text` into its orthographic syllables, one per grapheme cluster.
+pub fn syllables(text: &str) -> impl Iterator<Item = Syllable<'_>> {
+    grapheme_clusters(text).map(Syllable::parse)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_clusters() {
+        assert_eq!(count_clusters(""), 0);
+        assert_eq!(count_clusters("ক"), 1);
+        assert_eq!(count_clusters("কা"), 1);
+        assert_eq!(count_clusters("ক্ষ"), 1);
+        assert_eq!(count_clusters("ক্ষ্ম"), 1);
+        assert_eq!(count_clusters("আমার"), 3);
+        assert_eq!(count_clusters("কম্পিউটার"), 5);
+        assert_eq!(count_clusters("বাংলা"), 2);
+        assert_eq!(count_clusters("দুঃখ"), 2);
+        assert_eq!(count_clusters("র‍্যাব"), 2);
+        assert_eq!(count_clusters("ক্\u{200C}ষ"), 2);
+    }
+
+    #[test]
+    fn test_grapheme_clusters() {
+        let clusters = |text| grapheme_clusters(text).collect::<Vec<_>>();
+
+        assert!(clusters("").is_empty());
+        assert_eq!(clusters("কম্পিউটার"), ["ক", "ম্পি", "উ", "টা", "র"]);
+        assert_eq!(clusters("লক্ষ্মী"), ["ল", "ক্ষ্মী"]);
+        assert_eq!(clusters("কর্মী"), ["ক", "র্মী"]);
+        assert_eq!(clusters("অ্যাপ"), ["অ্যা", "প"]);
+        assert_eq!(clusters("পা\u{09DC}া"), ["পা", "\u{09DC}া"]);
+        assert_eq!(clusters("দুঃখ"), ["দুঃ", "খ"]);
+        assert_eq!(clusters("ক্\u{200C}ষ"), ["ক্\u{200C}", "ষ"]);
+        assert_eq!(clusters("১২ কি"), ["১", "২", " ", "কি"]);
+        // A sign with no letter before it is a cluster of its own
+        assert_eq!(clusters("িক"), ["ি", "ক"]);
+    }
+
+    #[test]
+    fn test_syllables() {
+        let syllable = |text| syllables(text).next().unwrap();
+
+        assert_eq!(
+            syllable("র্ব্যে"),
+            Syllable {
+                text: "র্ব্যে",
+                reph: true,
+                base: "ব",
+                ya_phala: true,
+                vowel_sign: Some('ে'),
+                signs: "",
+            }
+        );
+        assert_eq!(
+            syllable("ক্ষ্মীঁ"),
+            Syllable {
+                text: "ক্ষ্মীঁ",
+                reph: false,
+                base: "ক্ষ্ম",
+                ya_phala: false,
+                vowel_sign: Some('ী'),
+                signs: "ঁ",
+            }
+        );
+
+        let parts = |text| {
+            syllables(text)
+                .map(|s| (s.reph, s.base, s.ya_phala, s.vowel_sign, s.signs))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            parts("বাংলা"),
+            [
+                (false, "ব", false, Some('া'), "ং"),
+                (false, "ল", false, Some('া'), "")
+            ]
+        );
+        assert_eq!(parts("অ্যা"), [(false, "অ", true, Some('া'), "")]);
+        // The ya-phala after র with a ZWJ is not a reph
+        assert_eq!(parts("র‍্যা"), [(false, "র\u{200D}", true, Some('া'), "")]);
+        assert_eq!(parts("য"), [(false, "য", false, None, "")]);
+        assert_eq!(parts("র্"), [(false, "র", false, None, "\u{09CD}")]);
+        assert_eq!(
+            parts("\u{09DC}ৌ"),
+            [(false, "\u{09DC}", false, Some('ৌ'), "")]
+        );
+        assert_eq!(
+            parts("ক্\u{200C}"),
+            [(false, "ক", false, None, "\u{09CD}\u{200C}")]
+        );
+    }
+}